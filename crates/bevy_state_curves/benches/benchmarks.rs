@@ -2,11 +2,17 @@ use bevy::{
     prelude::{Component, ReflectComponent},
     reflect::Reflect,
 };
+use std::{collections::BTreeMap, hint::black_box};
+
 use bevy_state_curves::prelude::{
-    CurveTrait, LinearCurve, LinearKeyframe, SteppedCurve, SteppedKeyframe,
+    BoundedStorage, CurveStorage, CurveTrait, GameTick, LinearCurve, LinearKeyframe, SteppedCurve,
+    SteppedKeyframe, VecStorage,
 };
 use criterion::{criterion_group, criterion_main, Criterion};
 
+/// How many ticks of history the rollback benchmark keeps
+const ROLLBACK_WINDOW: u64 = 16;
+
 pub fn criterion_benchmark(c: &mut Criterion) {
     let mut object_state = BodyCurves::new();
     object_state
@@ -46,7 +52,34 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, criterion_benchmark);
+/// Simulates rollback netcode: a keyframe is inserted every tick, only the last [`ROLLBACK_WINDOW`] ticks are kept and
+/// the state is sampled in the middle of the window
+fn rollback<S: CurveStorage<GameTick, BodyAngle>>(ticks: u64) {
+    let mut curve = LinearCurve::<BodyAngle, GameTick, S>::default();
+    for tick in 0..ticks {
+        curve.insert_keyframe(tick, BodyAngle { angle: tick as f32 });
+        if tick >= ROLLBACK_WINDOW {
+            curve.remove_past_keyframes(&(tick - ROLLBACK_WINDOW));
+        }
+        black_box(curve.get_state(tick.saturating_sub(ROLLBACK_WINDOW / 2)));
+    }
+}
+
+pub fn storage_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rollback window");
+    group.bench_function("BTreeMap", |b| {
+        b.iter(|| rollback::<BTreeMap<GameTick, BodyAngle>>(1000))
+    });
+    group.bench_function("VecStorage", |b| {
+        b.iter(|| rollback::<VecStorage<GameTick, BodyAngle>>(1000))
+    });
+    group.bench_function("BoundedStorage", |b| {
+        b.iter(|| rollback::<BoundedStorage<GameTick, BodyAngle, 17>>(1000))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark, storage_benchmark);
 criterion_main!(benches);
 
 #[derive(Component)]
@@ -71,66 +104,37 @@ impl BodyCurves {
 }
 
 /// This component tracks the current angle of the body
-#[derive(Reflect, Clone, Component, Default, PartialEq, Debug)]
+#[derive(Reflect, Clone, Component, Default, PartialEq, Debug, LinearKeyframe)]
 #[reflect(Component)]
 pub struct BodyAngle {
     angle: f32,
 }
 
-impl LinearKeyframe<BodyAngle> for BodyAngle {
-    fn lerp(&self, next_frame_state: &BodyAngle, ratio: f64) -> BodyAngle {
-        BodyAngle {
-            angle: self.angle + (next_frame_state.angle - self.angle) * ratio as f32,
-        }
-    }
-}
-
 /// This component tracks the current angle of the body
-#[derive(Reflect, Clone, Component, Default, PartialEq, Debug)]
+#[derive(Reflect, Clone, Component, Default, PartialEq, Debug, LinearKeyframe)]
 #[reflect(Component)]
 pub struct BodyRadius {
     radius: f32,
 }
 
-impl LinearKeyframe<BodyRadius> for BodyRadius {
-    fn lerp(&self, next_frame_state: &BodyRadius, ratio: f64) -> BodyRadius {
-        BodyRadius {
-            radius: self.radius + (next_frame_state.radius - self.radius) * ratio as f32,
-        }
-    }
-}
-
 /// This component tracks the position that this body is rotating around
-#[derive(Reflect, Clone, Component, Default, PartialEq, Debug)]
+#[derive(Reflect, Clone, Component, Default, PartialEq, Debug, LinearKeyframe)]
 #[reflect(Component)]
 pub struct BodyRotationPoint {
     point_x: f32,
     point_y: f32,
 }
 
-impl LinearKeyframe<BodyRotationPoint> for BodyRotationPoint {
-    fn lerp(&self, next_frame_state: &BodyRotationPoint, ratio: f64) -> BodyRotationPoint {
-        BodyRotationPoint {
-            point_x: self.point_x + (next_frame_state.point_x - self.point_x) * ratio as f32,
-            point_y: self.point_y + (next_frame_state.point_y - self.point_y) * ratio as f32,
-        }
-    }
-}
-
 /// This component represents how many ticks it takes to complete a full orbit
-#[derive(Reflect, Clone, Component, Default, PartialEq, Debug)]
+#[derive(Reflect, Clone, Component, Default, PartialEq, Debug, SteppedKeyframe)]
 #[reflect(Component)]
 pub struct BodySpeed {
     speed: u32,
 }
 
-impl SteppedKeyframe<BodySpeed> for BodySpeed {}
-
 /// This component tracks how many orbits the object has done
-#[derive(Reflect, Clone, Component, Default, PartialEq, Debug)]
+#[derive(Reflect, Clone, Component, Default, PartialEq, Debug, SteppedKeyframe)]
 #[reflect(Component)]
 pub struct BodyOrbit {
     orbits: u32,
 }
-
-impl SteppedKeyframe<BodyOrbit> for BodyOrbit {}
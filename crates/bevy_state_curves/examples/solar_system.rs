@@ -2,16 +2,20 @@ use std::time::Duration;
 
 use bevy::{
     prelude::{
-        default, App, AssetServer, Camera2d, Commands, Component, Entity, FixedUpdate, Query, Res,
-        ResMut, Resource, Startup, Transform, Update, Vec2, Vec3,
+        default, App, AssetServer, Camera2d, Commands, Component, FixedUpdate, Image,
+        IntoSystemConfigs, Query, Res, ResMut, Startup, Transform, Update, Vec2, Vec3,
     },
+    reflect::TypePath,
     sprite::Sprite,
     time::{Fixed, Time},
     DefaultPlugins,
 };
 use bevy_state_curves::prelude::{
-    CurveTrait, GameTick, LinearCurve, LinearKeyframe, SteppedCurve, SteppedKeyframe,
+    CurveAssetHandle, CurveSystems, CurveTrait, CurvesPlugin, LinearCurve, LinearKeyframe,
+    RegisterCurve, RegisterCurveAsset, RewindCommands, SteppedCurve, SteppedKeyframe, Timeline,
+    TimelinePlugin,
 };
+use serde::Deserialize;
 
 use bevy_egui::{
     egui::{self, Align2, Color32, Frame, Margin, Stroke},
@@ -23,28 +27,26 @@ extern crate bevy_state_curves;
 fn main() {
     let mut app = App::new();
 
-    app.add_plugins((DefaultPlugins, EguiPlugin));
+    app.add_plugins((DefaultPlugins, EguiPlugin, CurvesPlugin, TimelinePlugin));
+    app.register_curve::<LinearCurve<BodyAngle>>()
+        .register_curve::<LinearCurve<BodyRotationPoint>>()
+        .register_curve::<SteppedCurve<BodyOrbit>>();
+    // The speed and radius of each body are authored in `assets/curves`
+    app.register_curve_asset::<LinearCurve<BodyRadius>>()
+        .register_curve_asset::<SteppedCurve<BodySpeed>>();
 
     app.insert_resource(Time::<Fixed>::from_duration(Duration::from_secs_f32(0.1)));
     app.add_systems(Startup, setup);
     app.add_systems(
         Update,
-        (
-            update_body_position,
-            update_body_components_for_viewed_tick,
-            ui,
-        ),
+        (update_body_position.after(CurveSystems::SampleState), ui),
     );
     app.add_systems(
         FixedUpdate,
-        (update_viewed_tick, simulation_tick, simulate_bodies),
+        simulate_bodies.after(CurveSystems::AdvanceTimeline),
     );
 
-    app.insert_resource(ViewedTick(0));
-    app.insert_resource(DisplayTimeForward);
-    app.insert_resource(SimulationTick(0));
-    app.insert_resource(SimulateGame);
-    app.insert_resource(LastStateUpdatedTick(999));
+    app.insert_resource(Timeline::with_horizon(FUTURE_SIMULATION_TICKS));
 
     app.run();
 }
@@ -60,201 +62,53 @@ const FUTURE_SIMULATION_TICKS: u64 = 300;
 
 fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands.spawn(Camera2d);
-    {
-        let mut object_state = BodyCurves::new();
-        object_state.rotation_point.insert_keyframe(
-            0,
-            BodyRotationPoint {
-                point_x: 0.0,
-                point_y: 0.0,
-            },
-        );
-        object_state
-            .speed
-            .insert_keyframe(0, BodySpeed { speed: 60 });
-        object_state
-            .radius
-            .insert_keyframe(0, BodyRadius { radius: 50.0 });
-        object_state
-            .angle
-            .insert_keyframe(0, BodyAngle { angle: 0.0 });
-        object_state
-            .angle
-            .insert_keyframe(60, BodyAngle { angle: 6.0 });
-
-        object_state
-            .orbit
-            .insert_keyframe(0, BodyOrbit { orbits: 0 });
-        object_state
-            .orbit
-            .insert_keyframe(60, BodyOrbit { orbits: 1 });
-
-        let body = commands.spawn_empty().id();
-
-        let frames = object_state.get_object_state_for_tick(0);
-
-        commands.entity(body).insert((
-            object_state,
-            Sprite {
-                image: asset_server.load("planet_three.png"),
-                ..default()
-            },
-            Transform::from_scale(Vec3::new(3.0, 3.0, 3.0)),
-            frames,
-        ));
-    }
-
-    {
-        let mut object_state = BodyCurves::new();
-        object_state.rotation_point.insert_keyframe(
-            0,
-            BodyRotationPoint {
-                point_x: 0.0,
-                point_y: 0.0,
-            },
-        );
-        object_state
-            .speed
-            .insert_keyframe(0, BodySpeed { speed: 30 });
-        object_state
-            .radius
-            .insert_keyframe(0, BodyRadius { radius: 100.0 });
-        object_state
-            .angle
-            .insert_keyframe(0, BodyAngle { angle: 0.0 });
-        object_state
-            .angle
-            .insert_keyframe(60, BodyAngle { angle: 6.0 });
-
-        object_state
-            .orbit
-            .insert_keyframe(0, BodyOrbit { orbits: 0 });
-        object_state
-            .orbit
-            .insert_keyframe(60, BodyOrbit { orbits: 1 });
-
-        let body = commands.spawn_empty().id();
-
-        let frames = object_state.get_object_state_for_tick(0);
-
-        commands.entity(body).insert((
-            object_state,
-            Sprite {
-                image: asset_server.load("planet_two.png"),
-                ..default()
-            },
-            Transform::from_scale(Vec3::new(4.0, 4.0, 4.0)),
-            frames,
-        ));
-    }
-
-    {
-        let mut object_state = BodyCurves::new();
-        object_state.rotation_point.insert_keyframe(
-            0,
-            BodyRotationPoint {
-                point_x: 0.0,
-                point_y: 0.0,
-            },
-        );
-        object_state
-            .speed
-            .insert_keyframe(0, BodySpeed { speed: 120 });
-        object_state
-            .radius
-            .insert_keyframe(0, BodyRadius { radius: 150.0 });
-        object_state
-            .angle
-            .insert_keyframe(0, BodyAngle { angle: 0.0 });
-        object_state
-            .angle
-            .insert_keyframe(60, BodyAngle { angle: 6.0 });
-
-        object_state
-            .orbit
-            .insert_keyframe(0, BodyOrbit { orbits: 0 });
-        object_state
-            .orbit
-            .insert_keyframe(60, BodyOrbit { orbits: 1 });
-
-        let body = commands.spawn_empty().id();
-
-        let frames = object_state.get_object_state_for_tick(0);
-
-        commands.entity(body).insert((
-            object_state,
-            Sprite {
-                image: asset_server.load("planet_one.png"),
-                ..default()
-            },
-            Transform::from_scale(Vec3::new(2.0, 2.0, 2.0)),
-            frames,
-        ));
-    }
 
-    {
-        let mut object_state = BodyCurves::new();
-        object_state.rotation_point.insert_keyframe(
-            0,
-            BodyRotationPoint {
-                point_x: 0.0,
-                point_y: 0.0,
-            },
-        );
-        object_state
-            .speed
-            .insert_keyframe(0, BodySpeed { speed: 25 });
-        object_state
-            .radius
-            .insert_keyframe(0, BodyRadius { radius: 300.0 });
-        object_state
-            .angle
-            .insert_keyframe(0, BodyAngle { angle: 0.0 });
-        object_state
-            .angle
-            .insert_keyframe(60, BodyAngle { angle: 6.0 });
-
-        object_state
-            .orbit
-            .insert_keyframe(0, BodyOrbit { orbits: 0 });
-        object_state
-            .orbit
-            .insert_keyframe(60, BodyOrbit { orbits: 1 });
-
-        let body = commands.spawn_empty().id();
-
-        let frames = object_state.get_object_state_for_tick(0);
-
-        commands.entity(body).insert((
-            object_state,
-            Sprite {
-                image: asset_server.load("moon.png"),
-                ..default()
-            },
-            Transform::from_scale(Vec3::new(2.0, 2.0, 2.0)),
-            frames,
-        ));
-    }
+    spawn_body(&mut commands, &asset_server, "planet_three", 3.0);
+    spawn_body(&mut commands, &asset_server, "planet_two", 4.0);
+    spawn_body(&mut commands, &asset_server, "planet_one", 2.0);
+    spawn_body(&mut commands, &asset_server, "moon", 2.0);
 }
 
-/// Ticks the simulation tick every fixed timestep no matter what
-fn simulation_tick(
-    mut simulation_tick: ResMut<SimulationTick>,
-    maybe_play: Option<Res<SimulateGame>>,
-) {
-    if maybe_play.is_some() {
-        simulation_tick.0 += 1;
-    }
-}
+/// Spawns a body with all of its curves. The [`CurvesPlugin`] takes care of inserting the current state for each curve
+///
+/// The speed and radius curves are loaded from `assets/curves/{name}` and inserted once the files are loaded.
+fn spawn_body(commands: &mut Commands, asset_server: &AssetServer, name: &str, scale: f32) {
+    let mut rotation_point = LinearCurve::new();
+    rotation_point.insert_keyframe(
+        0,
+        BodyRotationPoint {
+            point_x: 0.0,
+            point_y: 0.0,
+        },
+    );
 
-/// Updates the viewed tick every fixed timestep if [`DisplayTimeForward`] exists.
-fn update_viewed_tick(
-    mut viewed_tick: ResMut<ViewedTick>,
-    maybe_play: Option<Res<DisplayTimeForward>>,
-) {
-    if maybe_play.is_some() {
-        viewed_tick.0 += 1;
-    }
+    let body_speed = CurveAssetHandle::<SteppedCurve<BodySpeed>>::new(
+        asset_server.load(format!("curves/{name}/speed.curve.ron")),
+    );
+    let body_radius = CurveAssetHandle::<LinearCurve<BodyRadius>>::new(
+        asset_server.load(format!("curves/{name}/radius.curve.ron")),
+    );
+
+    let mut angle = LinearCurve::new();
+    angle.insert_keyframe(0, BodyAngle { angle: 0.0 });
+    angle.insert_keyframe(60, BodyAngle { angle: 6.0 });
+
+    let mut orbit = SteppedCurve::new();
+    orbit.insert_keyframe(0, BodyOrbit { orbits: 0 });
+    orbit.insert_keyframe(60, BodyOrbit { orbits: 1 });
+
+    commands.spawn((
+        rotation_point,
+        body_speed,
+        body_radius,
+        angle,
+        orbit,
+        Sprite {
+            image: asset_server.load::<Image>(format!("{name}.png")),
+            ..default()
+        },
+        Transform::from_scale(Vec3::splat(scale)),
+    ));
 }
 
 /// This simulates all the bodies into the future every tick.
@@ -263,40 +117,45 @@ fn update_viewed_tick(
 /// It then checks if there are any keyframes that exist past that tick. If there are not then it goes to the furthest tick and
 /// simulates the correct distance after and so on
 fn simulate_bodies(
-    simulation_tick: Res<SimulationTick>,
-    mut bodies: Query<(Entity, &mut BodyCurves)>,
+    timeline: Res<Timeline>,
+    mut bodies: Query<(
+        &mut LinearCurve<BodyAngle>,
+        &mut SteppedCurve<BodyOrbit>,
+        &SteppedCurve<BodySpeed>,
+    )>,
 ) {
-    for (_entity, mut object_state) in bodies.iter_mut() {
-        let mut farthest_state = simulation_tick.0;
+    for (mut angle, mut orbit, speed) in bodies.iter_mut() {
+        let mut farthest_state = timeline.simulation_tick();
         let mut last_angle = 0f32;
 
-        for (tick, frame) in object_state.angle.iter_future_curves(simulation_tick.0) {
+        if let Some((tick, frame)) = angle
+            .iter_future_curves(timeline.simulation_tick())
+            .next_back()
+        {
             farthest_state = *tick;
             last_angle = frame.angle;
         }
 
         if farthest_state < farthest_state + FUTURE_SIMULATION_TICKS {
-            let body_orbit = object_state.orbit.get_state(farthest_state).unwrap();
+            let body_orbit = orbit.get_state(farthest_state).unwrap();
 
             let mut last_orbit_count = body_orbit.orbits;
 
-            let object_speed = object_state.speed.get_state(farthest_state).unwrap();
+            let object_speed = speed.get_state(farthest_state).unwrap();
 
             let mut i: u64 = farthest_state + object_speed.speed as u64;
             while i < farthest_state + FUTURE_SIMULATION_TICKS {
-                last_angle = last_angle + CIRCLE_ANGLE;
-                last_orbit_count = last_orbit_count + ORBIT_AMOUNT;
-                object_state
-                    .angle
-                    .insert_keyframe(i, BodyAngle { angle: last_angle });
-                object_state.orbit.insert_keyframe(
+                last_angle += CIRCLE_ANGLE;
+                last_orbit_count += ORBIT_AMOUNT;
+                angle.insert_keyframe(i, BodyAngle { angle: last_angle });
+                orbit.insert_if_changed(
                     i,
                     BodyOrbit {
                         orbits: last_orbit_count,
                     },
                 );
 
-                let object_speed = object_state.speed.get_state(i).unwrap();
+                let object_speed = speed.get_state(i).unwrap();
                 i += object_speed.speed as u64;
             }
         }
@@ -304,155 +163,56 @@ fn simulate_bodies(
 }
 
 /// Updates all bodies positions to the correct spot based on their current components.
-fn update_body_position(mut bodies: Query<(&mut Transform, &BodyAngle, &BodyOrbit, &BodyRadius)>) {
-    for (mut transform, angle, _orbit, radius) in bodies.iter_mut() {
+///
+/// The angle is sampled in between fixed updates so that the bodies move smoothly no matter the fixed timestep.
+fn update_body_position(
+    timeline: Res<Timeline>,
+    fixed_time: Res<Time<Fixed>>,
+    mut bodies: Query<(&mut Transform, &LinearCurve<BodyAngle>, &BodyRadius)>,
+) {
+    let tick = timeline.viewed_tick_fractional(fixed_time.overstep_fraction_f64());
+    for (mut transform, angle_curve, radius) in bodies.iter_mut() {
+        let Some(angle) = angle_curve.get_state_at(tick) else {
+            continue;
+        };
         let x = angle.angle.cos() * radius.radius;
         let y = angle.angle.sin() * radius.radius;
         transform.translation = Vec2::new(x, y).extend(1.0)
     }
 }
 
-/// Updates all bodies to have the correct components for the given tick
-fn update_body_components_for_viewed_tick(
-    bodies: Query<(Entity, &BodyCurves)>,
-    viewed_tick: Res<ViewedTick>,
-    mut last_state_updated_tick: ResMut<LastStateUpdatedTick>,
-    mut commands: Commands,
-) {
-    if viewed_tick.0 == last_state_updated_tick.0 {
-        return;
-    }
-    for (entity, object_state) in bodies.iter() {
-        let frames = object_state.get_object_state_for_tick(viewed_tick.0);
-        commands.entity(entity).insert(frames);
-    }
-
-    last_state_updated_tick.0 = viewed_tick.0;
-}
-
-#[derive(Resource)]
-pub struct ViewedTick(GameTick);
-
-#[derive(Resource)]
-pub struct DisplayTimeForward;
-
-#[derive(Resource)]
-pub struct SimulationTick(GameTick);
-
-#[derive(Resource)]
-pub struct SimulateGame;
-
-#[derive(Resource)]
-pub struct LastStateUpdatedTick(GameTick);
-
-#[derive(Component)]
-struct BodyCurves {
-    angle: LinearCurve<BodyAngle>,
-    radius: LinearCurve<BodyRadius>,
-    rotation_point: LinearCurve<BodyRotationPoint>,
-    speed: SteppedCurve<BodySpeed>,
-    orbit: SteppedCurve<BodyOrbit>,
-}
-
-impl BodyCurves {
-    fn new() -> BodyCurves {
-        BodyCurves {
-            angle: LinearCurve::new(),
-            radius: LinearCurve::new(),
-            rotation_point: LinearCurve::new(),
-            speed: SteppedCurve::new(),
-            orbit: SteppedCurve::new(),
-        }
-    }
-
-    fn get_object_state_for_tick(
-        &self,
-        tick: GameTick,
-    ) -> (
-        BodyAngle,
-        BodyRadius,
-        BodyRotationPoint,
-        BodySpeed,
-        BodyOrbit,
-    ) {
-        (
-            self.angle.get_state(tick).unwrap(),
-            self.radius.get_state(tick).unwrap(),
-            self.rotation_point.get_state(tick).unwrap(),
-            self.speed.get_state(tick).unwrap(),
-            self.orbit.get_state(tick).unwrap(),
-        )
-    }
-}
-
 /// This component tracks the current angle of the body
-#[derive(Clone, Component)]
+#[derive(Clone, Component, LinearKeyframe)]
 pub struct BodyAngle {
     angle: f32,
 }
 
-impl LinearKeyframe<BodyAngle> for BodyAngle {
-    fn lerp(&self, next_frame_state: &BodyAngle, ratio: f64) -> BodyAngle {
-        BodyAngle {
-            angle: self.angle + (next_frame_state.angle - self.angle) * ratio as f32,
-        }
-    }
-}
-
 /// This component tracks the current angle of the body
-#[derive(Clone, Component)]
+#[derive(Clone, Component, LinearKeyframe, Deserialize, TypePath)]
 pub struct BodyRadius {
     radius: f32,
 }
 
-impl LinearKeyframe<BodyRadius> for BodyRadius {
-    fn lerp(&self, next_frame_state: &BodyRadius, ratio: f64) -> BodyRadius {
-        BodyRadius {
-            radius: self.radius + (next_frame_state.radius - self.radius) * ratio as f32,
-        }
-    }
-}
-
 /// This component tracks the position that this body is rotating around
-#[derive(Clone, Component)]
+#[derive(Clone, Component, LinearKeyframe)]
 pub struct BodyRotationPoint {
     point_x: f32,
     point_y: f32,
 }
 
-impl LinearKeyframe<BodyRotationPoint> for BodyRotationPoint {
-    fn lerp(&self, next_frame_state: &BodyRotationPoint, ratio: f64) -> BodyRotationPoint {
-        BodyRotationPoint {
-            point_x: self.point_x + (next_frame_state.point_x - self.point_x) * ratio as f32,
-            point_y: self.point_y + (next_frame_state.point_y - self.point_y) * ratio as f32,
-        }
-    }
-}
-
 /// This component represents how many ticks it takes to complete a full orbit
-#[derive(Clone, Component)]
+#[derive(Clone, Component, SteppedKeyframe, Deserialize, TypePath)]
 pub struct BodySpeed {
     speed: u32,
 }
 
-impl SteppedKeyframe<BodySpeed> for BodySpeed {}
-
 /// This component tracks how many orbits the object has done
-#[derive(Clone, Component)]
+#[derive(Clone, PartialEq, Component, SteppedKeyframe)]
 pub struct BodyOrbit {
     orbits: u32,
 }
 
-impl SteppedKeyframe<BodyOrbit> for BodyOrbit {}
-
-fn ui(
-    mut egui_context: EguiContexts,
-    option_playing: Option<Res<DisplayTimeForward>>,
-    option_simulate: Option<Res<SimulateGame>>,
-    simulation_tick: Res<SimulationTick>,
-    viewed_tick: Res<ViewedTick>,
-    mut commands: Commands,
-) {
+fn ui(mut egui_context: EguiContexts, mut timeline: ResMut<Timeline>, mut commands: Commands) {
     let settings_frame = Frame {
         fill: Color32::from_rgba_unmultiplied(0, 0, 0, 255),
         stroke: Stroke::new(0., Color32::WHITE),
@@ -471,36 +231,32 @@ fn ui(
         .collapsible(true)
         .title_bar(true)
         .show(egui_context.ctx_mut(), |ui| {
-            ui.label(format!("Simulation Tick: {:?}", simulation_tick.0));
-            ui.label(format!("Viewed Tick: {:?}", viewed_tick.0));
+            ui.label(format!("Simulation Tick: {:?}", timeline.simulation_tick()));
+            ui.label(format!("Viewed Tick: {:?}", timeline.viewed_tick()));
 
-            if option_playing.is_none() {
+            if timeline.paused {
                 if ui.button("Animate Simulation").clicked() {
-                    commands.insert_resource(DisplayTimeForward);
-                }
-            } else {
-                if ui.button("Stop Animating Simulation").clicked() {
-                    commands.remove_resource::<DisplayTimeForward>();
+                    timeline.play();
                 }
+            } else if ui.button("Stop Animating Simulation").clicked() {
+                timeline.pause();
             }
-            if option_simulate.is_none() {
+            if !timeline.simulating {
                 if ui.button("Start Simulation").clicked() {
-                    commands.insert_resource(SimulateGame);
-                }
-            } else {
-                if ui.button("Stop Simulation").clicked() {
-                    commands.remove_resource::<SimulateGame>();
+                    timeline.simulating = true;
                 }
+            } else if ui.button("Stop Simulation").clicked() {
+                timeline.simulating = false;
             }
 
+            ui.add(egui::Slider::new(&mut timeline.rate, 0.0..=5.0).text("Playback Rate"));
+
             let response = ui
                 .button("Skip Forward")
                 .interact(egui::Sense::click_and_drag());
 
             if response.dragged() {
-                commands.insert_resource(ViewedTick(
-                    (viewed_tick.0 + 1).clamp(0, simulation_tick.0 + FUTURE_SIMULATION_TICKS),
-                ));
+                timeline.scrub_by(1);
             }
 
             let response = ui
@@ -508,16 +264,16 @@ fn ui(
                 .interact(egui::Sense::click_and_drag());
 
             if response.dragged() {
-                commands.insert_resource(ViewedTick(
-                    (viewed_tick.0.saturating_sub(1))
-                        .clamp(0, simulation_tick.0 + FUTURE_SIMULATION_TICKS),
-                ));
+                timeline.scrub_by(-1);
             }
 
-            if simulation_tick.0 != viewed_tick.0 {
-                if ui.button("View Current Animation").clicked() {
-                    commands.insert_resource(ViewedTick(simulation_tick.0));
-                }
+            if !timeline.is_viewing_present() && ui.button("View Current Animation").clicked() {
+                timeline.view_present();
+            }
+
+            // Throws away everything simulated after the viewed tick and continues the simulation from there
+            if !timeline.is_viewing_present() && ui.button("Branch From Viewed Tick").clicked() {
+                commands.rewind_to(timeline.viewed_tick());
             }
         });
 }
@@ -0,0 +1,251 @@
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use bevy::{
+    app::{App, Update},
+    asset::{
+        io::Reader, ron, Asset, AssetApp, AssetEvent, AssetLoader, Assets, Handle, LoadContext,
+    },
+    prelude::{
+        Commands, Component, DetectChanges, Entity, EventReader, IntoSystemConfigs,
+        IntoSystemSetConfigs, Query, Ref, Res,
+    },
+    reflect::TypePath,
+    utils::HashSet,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    curves::CurveTrait,
+    error::CurveAssetError,
+    plugin::{ComponentCurve, CurveSystems, RegisterCurve},
+    GameTick,
+};
+
+/// Keyframes authored as data, loaded from `.curve.ron` files by the [`CurveAssetLoader`].
+///
+/// Reference it from an entity with a [`CurveAssetHandle`] to have its keyframes loaded into the entity's curve. The
+/// file holds a map from tick to keyframe:
+///
+/// ```ron
+/// (
+///     keyframes: {
+///         0: (speed: 60),
+///         600: (speed: 30),
+///     },
+/// )
+/// ```
+#[derive(Asset, TypePath, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CurveAsset<T: Send + Sync + TypePath> {
+    /// The keyframes of the curve, by tick
+    pub keyframes: BTreeMap<GameTick, T>,
+}
+
+impl<T: Clone + Send + Sync + TypePath> CurveAsset<T> {
+    /// Creates a curve `C` holding every keyframe of the asset
+    pub fn to_curve<C: CurveTrait<T>>(&self) -> C {
+        let mut curve = C::new();
+        for (tick, keyframe) in self.keyframes.iter() {
+            curve.insert_keyframe(*tick, keyframe.clone());
+        }
+        curve
+    }
+}
+
+/// Loads [`CurveAsset`]s from `.curve.ron` files. Added by [`RegisterCurveAsset::register_curve_asset`].
+///
+/// A loader is added for every keyframe type so load files with a typed handle, such as
+/// `asset_server.load::<CurveAsset<BodySpeed>>("speed.curve.ron")`, for bevy to pick the right one.
+pub struct CurveAssetLoader<T> {
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for CurveAssetLoader<T> {
+    fn default() -> Self {
+        CurveAssetLoader {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: DeserializeOwned + Send + Sync + TypePath> AssetLoader for CurveAssetLoader<T> {
+    type Asset = CurveAsset<T>;
+    type Settings = ();
+    type Error = CurveAssetError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<CurveAsset<T>, CurveAssetError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["curve.ron"]
+    }
+}
+
+/// Loads the keyframes of a [`CurveAsset`] into the curve `C` on this entity.
+///
+/// ## Explanation:
+///
+/// - Once the asset is loaded the curve is replaced with one holding exactly the keyframes of the asset. The curve is
+///   inserted if the entity doesn't have one yet.
+/// - The curve is replaced again every time the asset is modified, so with bevy's "file_watcher" feature editing the
+///   file hot-reloads the curve.
+/// - Changing the handle loads the keyframes of the new asset.
+///
+/// ```rust,ignore
+/// app.register_curve_asset::<SteppedCurve<BodySpeed>>();
+///
+/// commands.spawn(CurveAssetHandle::<SteppedCurve<BodySpeed>>::new(
+///     asset_server.load("speed.curve.ron"),
+/// ));
+/// ```
+#[derive(Component)]
+pub struct CurveAssetHandle<C: ComponentCurve>
+where
+    C::State: Send + Sync + TypePath,
+{
+    /// The asset that the keyframes are loaded from
+    pub handle: Handle<CurveAsset<C::State>>,
+}
+
+impl<C: ComponentCurve> CurveAssetHandle<C>
+where
+    C::State: Send + Sync + TypePath,
+{
+    /// Creates a component loading the keyframes of the asset into the curve `C`
+    pub fn new(handle: Handle<CurveAsset<C::State>>) -> CurveAssetHandle<C> {
+        CurveAssetHandle { handle }
+    }
+}
+
+/// Extension trait on [`App`] for loading curves from [`CurveAsset`]s
+pub trait RegisterCurveAsset {
+    /// Registers the curve `C`, see [`RegisterCurve::register_curve`], adds the [`CurveAssetLoader`] for its keyframes
+    /// and loads [`CurveAsset`]s into the curves of entities with a [`CurveAssetHandle<C>`].
+    ///
+    /// Requires bevy's `AssetPlugin`.
+    fn register_curve_asset<C: ComponentCurve>(&mut self) -> &mut Self
+    where
+        C::State: DeserializeOwned + Send + Sync + TypePath;
+}
+
+impl RegisterCurveAsset for App {
+    fn register_curve_asset<C: ComponentCurve>(&mut self) -> &mut Self
+    where
+        C::State: DeserializeOwned + Send + Sync + TypePath,
+    {
+        self.register_curve::<C>();
+        // Several curve types can share a keyframe type and with it the asset
+        if !self
+            .world()
+            .contains_resource::<Assets<CurveAsset<C::State>>>()
+        {
+            self.init_asset::<CurveAsset<C::State>>()
+                .init_asset_loader::<CurveAssetLoader<C::State>>();
+        }
+        self.configure_sets(
+            Update,
+            CurveSystems::LoadAssets.before(CurveSystems::SampleState),
+        );
+        self.add_systems(
+            Update,
+            load_curve_assets::<C>.in_set(CurveSystems::LoadAssets),
+        )
+    }
+}
+
+/// Replaces the curve of every entity whose [`CurveAssetHandle`] changed or whose asset was loaded or modified
+fn load_curve_assets<C: ComponentCurve>(
+    mut commands: Commands,
+    mut asset_events: EventReader<AssetEvent<CurveAsset<C::State>>>,
+    assets: Res<Assets<CurveAsset<C::State>>>,
+    handles: Query<(Entity, Ref<CurveAssetHandle<C>>)>,
+) where
+    C::State: Send + Sync + TypePath,
+{
+    let loaded: HashSet<_> = asset_events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+
+    for (entity, curve_handle) in handles.iter() {
+        if !curve_handle.is_changed() && !loaded.contains(&curve_handle.handle.id()) {
+            continue;
+        }
+        let Some(asset) = assets.get(&curve_handle.handle) else {
+            continue;
+        };
+        commands.entity(entity).insert(asset.to_curve::<C>());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use bevy::{
+        app::App,
+        asset::{ron, AssetPlugin, Assets},
+        prelude::{Component, MinimalPlugins},
+        reflect::TypePath,
+    };
+    use serde::Deserialize;
+
+    use super::{CurveAsset, CurveAssetHandle, RegisterCurveAsset};
+    use crate::prelude::{CurveTrait, CurvesPlugin, SteppedCurve, SteppedKeyframe, ViewedTick};
+
+    #[derive(Component, Clone, Debug, PartialEq, Deserialize, TypePath)]
+    struct Speed {
+        speed: u32,
+    }
+
+    impl SteppedKeyframe<Speed> for Speed {}
+
+    #[test]
+    fn test_curve_asset() {
+        let asset: CurveAsset<Speed> =
+            ron::de::from_str("(keyframes: { 0: (speed: 60), 600: (speed: 30) })").unwrap();
+        let curve = asset.to_curve::<SteppedCurve<Speed>>();
+        assert_eq!(curve.get_state(599), Some(Speed { speed: 60 }));
+        assert_eq!(curve.get_state(600), Some(Speed { speed: 30 }));
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), CurvesPlugin));
+        app.register_curve_asset::<SteppedCurve<Speed>>();
+
+        let handle = app
+            .world_mut()
+            .resource_mut::<Assets<CurveAsset<Speed>>>()
+            .add(asset);
+        let entity = app
+            .world_mut()
+            .spawn(CurveAssetHandle::<SteppedCurve<Speed>>::new(handle.clone()))
+            .id();
+        app.world_mut().resource_mut::<ViewedTick>().0 = 10;
+        app.update();
+        let curve = app.world().get::<SteppedCurve<Speed>>(entity).unwrap();
+        assert_eq!(curve.get_state(600), Some(Speed { speed: 30 }));
+        assert_eq!(app.world().get::<Speed>(entity), Some(&Speed { speed: 60 }));
+
+        // Modifying the asset replaces the keyframes of the curve
+        app.world_mut()
+            .resource_mut::<Assets<CurveAsset<Speed>>>()
+            .get_mut(&handle)
+            .unwrap()
+            .keyframes = BTreeMap::from([(0, Speed { speed: 10 })]);
+        app.update();
+        app.update();
+        let curve = app.world().get::<SteppedCurve<Speed>>(entity).unwrap();
+        assert_eq!(curve.get_state(600), Some(Speed { speed: 10 }));
+        assert_eq!(app.world().get::<Speed>(entity), Some(&Speed { speed: 10 }));
+    }
+}
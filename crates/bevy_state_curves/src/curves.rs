@@ -1,9 +1,20 @@
-use std::{collections::BTreeMap, ops::Bound};
+use std::{
+    collections::BTreeMap,
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+    sync::atomic::{AtomicU64, Ordering},
+};
 
 use bevy::prelude::Component;
 
 use crate::{
-    keyframe_trait::{LinearKeyframe, PulseKeyframe, SteppedKeyframe},
+    error::{check_frozen, CurveError},
+    keyframe_trait::{
+        CubicKeyframe, KeyframeDistance, LinearKeyframe, PhysicsKeyframe, PulseKeyframe,
+        SteppedKeyframe,
+    },
+    storage::CurveStorage,
+    tick::Tick,
     GameTick,
 };
 
@@ -11,8 +22,20 @@ use crate::{
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "type_path")]
+use bevy::reflect::impl_type_path;
+
+#[cfg(feature = "reflect")]
 use bevy::reflect::TypePath;
 
+#[cfg(feature = "reflect")]
+use {
+    crate::reflect::ReflectCurve,
+    bevy::{
+        ecs::reflect::ReflectComponent,
+        reflect::{FromReflect, Reflect},
+    },
+};
+
 /// The generic curve storage. This backs all the specific curve types storages internally
 ///
 /// # Implementing a new curve
@@ -34,158 +57,401 @@ use bevy::reflect::TypePath;
 ///    /// ... Implementation skipped for brevity. See source docs for examples ...
 ///}
 /// ```
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct Curve<V> {
-    map: BTreeMap<GameTick, V>,
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(where V: Send + Sync))]
+pub struct Curve<V, K: Tick = GameTick, S: CurveStorage<K, V> = BTreeMap<K, V>> {
+    storage: S,
+    #[cfg_attr(feature = "serde", serde(default = "Option::default"))]
+    frozen: Option<K>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[cfg_attr(feature = "reflect", reflect(ignore))]
+    id: CurveId,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[cfg_attr(feature = "reflect", reflect(ignore))]
+    revision: u64,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[cfg_attr(feature = "reflect", reflect(ignore))]
+    marker: PhantomData<fn() -> (K, V)>,
+}
+
+/// A number unique to every [`Curve`], telling apart curves that are at the same revision. Created, cloned and
+/// deserialized curves all get a new id.
+#[derive(Debug)]
+struct CurveId(u64);
+
+impl Default for CurveId {
+    fn default() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        CurveId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl Clone for CurveId {
+    fn clone(&self) -> Self {
+        CurveId::default()
+    }
 }
 
-impl<T> Curve<T> {
-    pub fn new() -> Curve<T> {
+impl<T, K: Tick, S: CurveStorage<K, T>> Curve<T, K, S> {
+    pub fn new() -> Curve<T, K, S> {
         Curve {
-            map: BTreeMap::new(),
+            storage: S::default(),
+            frozen: None,
+            id: CurveId::default(),
+            revision: 0,
+            marker: PhantomData,
         }
     }
-    /// Inserts a keyframe of the given type into the storage at the given [`GameTick`]
-    pub fn insert_keyframe(&mut self, tick: GameTick, keyframe: T) {
-        self.map.insert(tick, keyframe);
+
+    /// Returns a counter that changes every time the curve is mutated, including through a mutable reference to a
+    /// keyframe. Used to tell if anything cached from the curve, such as a [`CurveCursor`](crate::sampling::CurveCursor), is still valid
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Returns a number unique to this curve. Clones of the curve get a different id
+    pub(crate) fn id(&self) -> u64 {
+        self.id.0
+    }
+
+    /// Returns the tick that history is frozen before, see [`CurveTrait::freeze_history`]
+    pub fn frozen_tick(&self) -> Option<K> {
+        self.frozen
+    }
+
+    /// Sets the tick that history is frozen before, or unfreezes history with `None`
+    pub fn set_frozen_tick(&mut self, tick: Option<K>) {
+        self.frozen = tick;
+    }
+
+    /// Returns the storage for mutation, bumping the revision
+    fn storage_mut(&mut self) -> &mut S {
+        self.revision = self.revision.wrapping_add(1);
+        &mut self.storage
+    }
+
+    /// Inserts a keyframe of the given type into the storage at the given [`GameTick`]. Returns the keyframe that the
+    /// storage had no room for, see [`CurveStorage::insert`]
+    pub fn insert_keyframe(&mut self, tick: K, keyframe: T) -> Option<(K, T)> {
+        self.storage_mut().insert(tick, keyframe)
     }
 
     /// Removes the keyframe at the given [`GameTick`] if there is one
-    pub fn remove_keyframe(&mut self, tick: GameTick) {
-        self.map.remove(&tick);
+    pub fn remove_keyframe(&mut self, tick: K) {
+        self.storage_mut().remove(&tick);
     }
 
     /// Gets a reference to the keyframe at the given [`GameTick`] if there is one
-    pub fn get_keyframe(&self, tick: GameTick) -> Option<&T> {
-        self.map.get(&tick)
+    pub fn get_keyframe(&self, tick: K) -> Option<&T> {
+        self.storage.get(&tick)
     }
 
     /// Mutable version of [`self::get_keyframe`]
-    pub fn get_keyframe_mut(&mut self, tick: GameTick) -> Option<&mut T> {
-        self.map.get_mut(&tick)
+    pub fn get_keyframe_mut(&mut self, tick: K) -> Option<&mut T> {
+        self.storage_mut().get_mut(&tick)
     }
 
-    /// Returns a vec of all keyframes that come on or ***AFTER*** the given [`GameTick`]
-    pub fn iter_future_curves(&self, tick: GameTick) -> Vec<(&GameTick, &T)> {
-        self.map
-            .range((Bound::Included(&tick), Bound::Unbounded))
-            .collect::<Vec<(&GameTick, &T)>>()
+    /// Returns an iterator over all keyframes that come on or ***AFTER*** the given [`GameTick`]
+    pub fn iter_future_curves(&self, tick: K) -> impl DoubleEndedIterator<Item = (&K, &T)> + '_ {
+        self.storage
+            .range((Bound::Included(tick), Bound::Unbounded))
     }
 
     /// Mutable version of [`self::iter_future_curves`]
-    pub fn iter_future_curves_mut(&mut self, tick: GameTick) -> Vec<(&GameTick, &mut T)> {
-        self.map
-            .range_mut((Bound::Included(&tick), Bound::Unbounded))
-            .collect::<Vec<(&GameTick, &mut T)>>()
+    pub fn iter_future_curves_mut(
+        &mut self,
+        tick: K,
+    ) -> impl DoubleEndedIterator<Item = (&K, &mut T)> + '_ {
+        self.storage_mut()
+            .range_mut((Bound::Included(tick), Bound::Unbounded))
     }
 
     /// Returns the kext keyframe, if it exists, that comes on or after the given [`GameTick`]
-    pub fn next_keyframe(&self, tick: GameTick) -> Option<(&GameTick, &T)> {
-        self.map
-            .range((Bound::Included(&tick), Bound::Unbounded))
+    pub fn next_keyframe(&self, tick: K) -> Option<(&K, &T)> {
+        self.storage
+            .range((Bound::Included(tick), Bound::Unbounded))
             .next()
     }
 
     /// Mutable version of [`self::next_keyframe`]
-    pub fn next_keyframe_mut(&mut self, tick: GameTick) -> Option<(&GameTick, &mut T)> {
-        self.map
-            .range_mut((Bound::Included(&tick), Bound::Unbounded))
+    pub fn next_keyframe_mut(&mut self, tick: K) -> Option<(&K, &mut T)> {
+        self.storage_mut()
+            .range_mut((Bound::Included(tick), Bound::Unbounded))
             .next()
     }
 
-    /// Returns a vec of all keyframes that come on or ***BEFORE*** the given [`GameTick`]
-    pub fn iter_prev_curves(&self, tick: GameTick) -> Vec<(&GameTick, &T)> {
-        self.map
-            .range((Bound::Unbounded, Bound::Included(&tick)))
-            .collect::<Vec<(&GameTick, &T)>>()
+    /// Returns an iterator over all keyframes that come on or ***BEFORE*** the given [`GameTick`]
+    pub fn iter_prev_curves(&self, tick: K) -> impl DoubleEndedIterator<Item = (&K, &T)> + '_ {
+        self.storage
+            .range((Bound::Unbounded, Bound::Included(tick)))
     }
 
     /// Mutable version of [`self::iter_prev_curves`]
-    pub fn iter_prev_curves_mut(&mut self, tick: GameTick) -> Vec<(&GameTick, &mut T)> {
-        self.map
-            .range_mut((Bound::Unbounded, Bound::Included(&tick)))
-            .collect::<Vec<(&GameTick, &mut T)>>()
+    pub fn iter_prev_curves_mut(
+        &mut self,
+        tick: K,
+    ) -> impl DoubleEndedIterator<Item = (&K, &mut T)> + '_ {
+        self.storage_mut()
+            .range_mut((Bound::Unbounded, Bound::Included(tick)))
+    }
+
+    /// Returns an iterator over all keyframes within the given range of ticks, eg `curve.range(10..20)`
+    pub fn range<R: RangeBounds<K>>(
+        &self,
+        range: R,
+    ) -> impl DoubleEndedIterator<Item = (&K, &T)> + '_ {
+        self.storage
+            .range((range.start_bound().cloned(), range.end_bound().cloned()))
+    }
+
+    /// Mutable version of [`self::range`]
+    pub fn range_mut<R: RangeBounds<K>>(
+        &mut self,
+        range: R,
+    ) -> impl DoubleEndedIterator<Item = (&K, &mut T)> + '_ {
+        self.storage_mut()
+            .range_mut((range.start_bound().cloned(), range.end_bound().cloned()))
     }
 
     /// Returns the previous keyframe, if it exists, that comes on or before the given [`GameTick`]
-    pub fn prev_keyframe(&self, tick: GameTick) -> Option<(&GameTick, &T)> {
-        self.map
-            .range((Bound::Unbounded, Bound::Included(&tick)))
+    pub fn prev_keyframe(&self, tick: K) -> Option<(&K, &T)> {
+        self.storage
+            .range((Bound::Unbounded, Bound::Included(tick)))
             .next_back()
     }
 
     /// Mutable version of [`self::prev_keyframe`]
-    pub fn prev_keyframe_mut(&mut self, tick: GameTick) -> Option<(&GameTick, &mut T)> {
-        self.map
-            .range_mut((Bound::Unbounded, Bound::Included(&tick)))
+    pub fn prev_keyframe_mut(&mut self, tick: K) -> Option<(&K, &mut T)> {
+        self.storage_mut()
+            .range_mut((Bound::Unbounded, Bound::Included(tick)))
+            .next_back()
+    }
+
+    /// Returns the keyframe, if it exists, that comes strictly before the given [`GameTick`]
+    pub fn keyframe_before(&self, tick: K) -> Option<(&K, &T)> {
+        self.storage
+            .range((Bound::Unbounded, Bound::Excluded(tick)))
+            .next_back()
+    }
+
+    /// Returns the keyframe, if it exists, that comes strictly after the given [`GameTick`]
+    pub fn keyframe_after(&self, tick: K) -> Option<(&K, &T)> {
+        self.storage
+            .range((Bound::Excluded(tick), Bound::Unbounded))
+            .next()
+    }
+
+    /// Returns the first keyframe in the curve, if there is one
+    pub fn first_keyframe(&self) -> Option<(&K, &T)> {
+        self.storage
+            .range((Bound::Unbounded, Bound::Unbounded))
+            .next()
+    }
+
+    /// Returns the last keyframe in the curve, if there is one
+    pub fn last_keyframe(&self) -> Option<(&K, &T)> {
+        self.storage
+            .range((Bound::Unbounded, Bound::Unbounded))
             .next_back()
     }
 
     /// Returns any keyframes on or after the given tick
-    pub fn remove_future_keyframes(&mut self, tick: &GameTick) {
-        self.map.retain(|frame_tick, _| frame_tick < tick);
+    pub fn remove_future_keyframes(&mut self, tick: &K) {
+        self.storage_mut().retain(|frame_tick, _| frame_tick < tick);
     }
 
     /// Returns any keyframes on or before the given tick
-    pub fn remove_past_keyframes(&mut self, tick: &GameTick) {
-        self.map.retain(|frame_tick, _| frame_tick > tick);
+    pub fn remove_past_keyframes(&mut self, tick: &K) {
+        self.storage_mut().retain(|frame_tick, _| frame_tick > tick);
+    }
+
+    /// Removes every keyframe before the given tick except the last one on or before it, so that the state on the
+    /// tick stays the same
+    pub fn remove_keyframes_before(&mut self, tick: &K) {
+        let Some((kept, _)) = self.prev_keyframe(*tick) else {
+            return;
+        };
+        let kept = *kept;
+        self.storage_mut()
+            .retain(|frame_tick, _| *frame_tick >= kept);
+    }
+}
+
+impl<T, K: Tick, S: CurveStorage<K, T>> Default for Curve<T, K, S> {
+    fn default() -> Self {
+        Curve::new()
     }
 }
 
 /// Core trait used to interact with all Curves
-pub trait CurveTrait<T> {
+pub trait CurveTrait<T, K: Tick = GameTick> {
     /// Creates a new [`Self`]
     fn new() -> Self;
 
-    /// Inserts a keyframe of the given type into the storage at the given [`GameTick`]
-    fn insert_keyframe(&mut self, tick: GameTick, keyframe: T);
+    /// Inserts a keyframe of the given type into the storage at the given [`GameTick`].
+    ///
+    /// Returns the keyframe that the curve's storage had no room for, either the oldest keyframe that was dropped to
+    /// make room or this keyframe if it wasn't stored. Only bounded storages, such as
+    /// [`BoundedStorage`](crate::storage::BoundedStorage), ever return one. See [`CurveStorage::insert`]
+    fn insert_keyframe(&mut self, tick: K, keyframe: T) -> Option<(K, T)>;
 
     /// Removes the keyframe at the given [`GameTick`] if there is one
-    fn remove_keyframe(&mut self, tick: GameTick);
+    fn remove_keyframe(&mut self, tick: K);
 
     /// Gets a reference to the keyframe at the given [`GameTick`] if there is one
-    fn get_keyframe(&self, tick: GameTick) -> Option<&T>;
+    fn get_keyframe(&self, tick: K) -> Option<&T>;
 
     /// Mutable version of [`self::get_keyframe`]
-    fn get_keyframe_mut(&mut self, tick: GameTick) -> Option<&mut T>;
+    fn get_keyframe_mut(&mut self, tick: K) -> Option<&mut T>;
 
-    /// Returns a vec of references to all keyframes that come ***AFTER*** the given [`GameTick`], excluding any keyframe that may exist on the requested tick
-    fn iter_future_curves(&self, tick: GameTick) -> Vec<(&GameTick, &T)>;
+    /// Returns an iterator over references to all keyframes that come ***AFTER*** the given [`GameTick`], excluding any keyframe that may exist on the requested tick
+    fn iter_future_curves<'a>(&'a self, tick: K) -> impl DoubleEndedIterator<Item = (&'a K, &'a T)>
+    where
+        T: 'a;
 
     /// Mutable version of [`self::iter_future_curves`]
-    fn iter_future_curves_mut(&mut self, tick: GameTick) -> Vec<(&GameTick, &mut T)>;
+    fn iter_future_curves_mut<'a>(
+        &'a mut self,
+        tick: K,
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a mut T)>
+    where
+        T: 'a;
 
     /// Returns a reference to the kext keyframe, if it exists, that comes after the given [`GameTick`]
-    fn next_keyframe(&self, tick: GameTick) -> Option<(&GameTick, &T)>;
+    fn next_keyframe(&self, tick: K) -> Option<(&K, &T)>;
 
     /// Mutable version of [`self::next_keyframe_mut`]
-    fn next_keyframe_mut(&mut self, tick: GameTick) -> Option<(&GameTick, &mut T)>;
+    fn next_keyframe_mut(&mut self, tick: K) -> Option<(&K, &mut T)>;
 
-    /// Returns a vec of references to all keyframes that come ***BEFORE*** the given [`GameTick`], excluding any keyframe that may exist on the requested tick
-    fn iter_prev_curves(&self, tick: GameTick) -> Vec<(&GameTick, &T)>;
+    /// Returns an iterator over references to all keyframes that come ***BEFORE*** the given [`GameTick`], excluding any keyframe that may exist on the requested tick
+    fn iter_prev_curves<'a>(&'a self, tick: K) -> impl DoubleEndedIterator<Item = (&'a K, &'a T)>
+    where
+        T: 'a;
 
     /// Mutable version of [`self::iter_prev_curves_mut`]
-    fn iter_prev_curves_mut(&mut self, tick: GameTick) -> Vec<(&GameTick, &mut T)>;
+    fn iter_prev_curves_mut<'a>(
+        &'a mut self,
+        tick: K,
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a mut T)>
+    where
+        T: 'a;
+
+    /// Returns an iterator over references to all keyframes within the given range of ticks, eg `curve.range(10..20)`.
+    /// A range without any ticks, such as one that starts after it ends, returns no keyframes
+    fn range<'a, R: RangeBounds<K>>(
+        &'a self,
+        range: R,
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a T)>
+    where
+        T: 'a;
 
     /// Returns a reference to the previous keyframe, if it exists, that comes before the given [`GameTick`]
-    fn prev_keyframe(&self, tick: GameTick) -> Option<(&GameTick, &T)>;
+    fn prev_keyframe(&self, tick: K) -> Option<(&K, &T)>;
 
     /// Mutable version of [`self::prev_keyframe_mut`]
-    fn prev_keyframe_mut(&mut self, tick: GameTick) -> Option<(&GameTick, &mut T)>;
+    fn prev_keyframe_mut(&mut self, tick: K) -> Option<(&K, &mut T)>;
+
+    /// Returns a reference to the first keyframe in the curve, if there is one
+    fn first_keyframe(&self) -> Option<(&K, &T)>;
+
+    /// Returns a reference to the last keyframe in the curve, if there is one
+    fn last_keyframe(&self) -> Option<(&K, &T)>;
 
     /// Returns the state of the curve at the given [`GameTick`].
     ///
     /// The implementation and logic of the returned state depends on the exact type of curve. See the curve object for details
-    fn get_state(&self, tick: GameTick) -> Option<T>;
+    fn get_state(&self, tick: K) -> Option<T>;
+
+    /// Returns the state of the curve at a fractional tick, eg `10.25` is a quarter of the way between tick 10 and 11.
+    ///
+    /// Use this to smoothly render state in between fixed updates, for example by sampling at the viewed tick plus
+    /// `Time<Fixed>::overstep_fraction`. Curves that interpolate use the fraction, all other curves return the state
+    /// of the tick the fraction falls within. Negative or non finite ticks return no state.
+    fn get_state_at(&self, tick: f64) -> Option<T> {
+        let (tick, _) = K::split_fractional(tick)?;
+        self.get_state(tick)
+    }
 
     /// Returns any keyframes on or after the given tick
-    fn remove_future_keyframes(&mut self, tick: &GameTick);
+    fn remove_future_keyframes(&mut self, tick: &K);
 
     /// Returns any keyframes on or before the given tick
-    fn remove_past_keyframes(&mut self, tick: &GameTick);
+    fn remove_past_keyframes(&mut self, tick: &K);
+
+    /// Removes every keyframe before the given tick except the last one on or before it.
+    ///
+    /// Unlike [`CurveTrait::remove_past_keyframes`] the keyframe that the state on the tick is derived from is kept,
+    /// so the state on the tick and every tick after it stays the same.
+    fn remove_keyframes_before(&mut self, tick: &K);
+
+    /// Returns the tick that history is frozen before, if it is frozen. See [`CurveTrait::freeze_history`]
+    fn frozen_tick(&self) -> Option<K>;
+
+    /// Sets the tick that history is frozen before, or unfreezes history with `None`
+    fn set_frozen_tick(&mut self, tick: Option<K>);
+
+    /// Freezes every keyframe before the given tick.
+    ///
+    /// The `try_*` methods refuse to insert or remove keyframes before the frozen tick with
+    /// [`CurveError::InsertBeforeFrozenTick`]. Use this to stop systems rewriting history that has already been
+    /// simulated, for example by freezing at the [`Timeline`](crate::timeline::Timeline)s simulation tick.
+    ///
+    /// Only the `try_*` methods check the frozen tick. The infallible methods, and everything built on them such as
+    /// [`CurveMut`](crate::events::CurveMut), [`RewindTo`](crate::rewind::RewindTo) and
+    /// [`CurveRetention`](crate::retention::CurveRetention), still edit frozen keyframes.
+    fn freeze_history(&mut self, tick: K) {
+        self.set_frozen_tick(Some(tick));
+    }
+
+    /// Fallible version of [`CurveTrait::insert_keyframe`] that refuses to insert before the frozen tick
+    fn try_insert_keyframe(
+        &mut self,
+        tick: K,
+        keyframe: T,
+    ) -> Result<Option<(K, T)>, CurveError<K>> {
+        check_frozen(self.frozen_tick(), tick)?;
+        Ok(self.insert_keyframe(tick, keyframe))
+    }
+
+    /// Fallible version of [`CurveTrait::remove_keyframe`] that refuses to remove before the frozen tick
+    fn try_remove_keyframe(&mut self, tick: K) -> Result<(), CurveError<K>> {
+        check_frozen(self.frozen_tick(), tick)?;
+        self.remove_keyframe(tick);
+        Ok(())
+    }
+
+    /// Fallible version of [`CurveTrait::remove_future_keyframes`] that refuses to remove keyframes before the frozen
+    /// tick
+    fn try_remove_future_keyframes(&mut self, tick: &K) -> Result<(), CurveError<K>> {
+        check_frozen(self.frozen_tick(), *tick)?;
+        self.remove_future_keyframes(tick);
+        Ok(())
+    }
+
+    /// Fallible version of [`CurveTrait::get_state`] that says why there is no state.
+    ///
+    /// Every curve in this crate overrides this with the actual reason. The default, for curves that don't, can't tell
+    /// why [`CurveTrait::get_state`] returned `None` and reports every failure as [`CurveError::NoKeyframesBefore`].
+    fn try_get_state(&self, tick: K) -> Result<T, CurveError<K>> {
+        self.get_state(tick)
+            .ok_or(CurveError::NoKeyframesBefore { tick })
+    }
 }
 
+// Implemented rather than derived so that the `FromReflect` bound reflection needs doesn't apply to the type path, which
+// only needs the keyframe type to have a path
+#[cfg(feature = "type_path")]
+impl_type_path!((in bevy_state_curves::curves) LinearCurve<T: LinearKeyframe<T>, K: Tick, S: CurveStorage<K, T>>);
+#[cfg(feature = "type_path")]
+impl_type_path!((in bevy_state_curves::curves) SteppedCurve<T: SteppedKeyframe<T>, K: Tick, S: CurveStorage<K, T>>);
+#[cfg(feature = "type_path")]
+impl_type_path!((in bevy_state_curves::curves) PulseCurve<T: PulseKeyframe<T>, K: Tick, S: CurveStorage<K, T>>);
+#[cfg(feature = "type_path")]
+impl_type_path!((in bevy_state_curves::curves) CubicCurve<T: CubicKeyframe<T>, K: Tick, S: CurveStorage<K, T>>);
+#[cfg(feature = "type_path")]
+impl_type_path!((in bevy_state_curves::curves) PhysicsCurve<T: PhysicsKeyframe<T>, K: Tick, S: CurveStorage<K, T>>);
+
 /// A Linear curve containing all keyframes that implement [`LinearKeyFrame`]
 ///
 /// ## Explanation:
@@ -196,103 +462,295 @@ pub trait CurveTrait<T> {
 /// - If there are no future keyframes then the last keyframe is used.
 /// - If there are no past keyframes then no state is returned.
 /// - Otherwise the returned state is a lerped representation of what the state should be on that tick.
-#[derive(Component, Debug)]
+#[derive(Component, Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[cfg_attr(feature = "type_path", derive(TypePath))]
-pub struct LinearCurve<T: LinearKeyframe<T>> {
-    curve: Curve<T>,
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Curve, type_path = false, where T: FromReflect + TypePath, K: TypePath, S: TypePath))]
+pub struct LinearCurve<
+    T: LinearKeyframe<T>,
+    K: Tick = GameTick,
+    S: CurveStorage<K, T> = BTreeMap<K, T>,
+> {
+    pub(crate) curve: Curve<T, K, S>,
 }
 
-impl<T: LinearKeyframe<T>> CurveTrait<T> for LinearCurve<T> {
-    fn new() -> LinearCurve<T> {
+impl<T: LinearKeyframe<T>, K: Tick, S: CurveStorage<K, T>> LinearCurve<T, K, S> {
+    /// Interpolates the state `fraction` of a tick after the given tick from the keyframe on or before the tick and the
+    /// keyframe after it, if there is one.
+    ///
+    /// Returns [`CurveError::InvalidInterpolation`] if the ratio between the keyframes isn't a finite number.
+    pub(crate) fn interpolate(
+        (prev_tick, prev): (&K, &T),
+        next: Option<(&K, &T)>,
+        tick: K,
+        fraction: f64,
+    ) -> Result<T, CurveError<K>> {
+        let Some((next_tick, next)) = next.filter(|_| *prev_tick != tick || fraction != 0.0) else {
+            return Ok(prev.clone());
+        };
+        let ratio = (prev_tick.distance(&tick) + fraction) / prev_tick.distance(next_tick);
+        if !ratio.is_finite() {
+            return Err(CurveError::InvalidInterpolation {
+                prev: *prev_tick,
+                next: *next_tick,
+                ratio,
+            });
+        }
+        Ok(prev.lerp(next, ratio))
+    }
+}
+
+impl<T: LinearKeyframe<T>, K: Tick, S: CurveStorage<K, T>> CurveTrait<T, K>
+    for LinearCurve<T, K, S>
+{
+    fn new() -> LinearCurve<T, K, S> {
         LinearCurve {
             curve: Curve::new(),
         }
     }
 
-    fn insert_keyframe(&mut self, tick: GameTick, keyframe: T) {
-        self.curve.insert_keyframe(tick, keyframe);
+    fn insert_keyframe(&mut self, tick: K, keyframe: T) -> Option<(K, T)> {
+        self.curve.insert_keyframe(tick, keyframe)
     }
 
-    fn remove_keyframe(&mut self, tick: GameTick) {
+    fn remove_keyframe(&mut self, tick: K) {
         self.curve.remove_keyframe(tick);
     }
 
-    fn get_keyframe(&self, tick: GameTick) -> Option<&T> {
+    fn get_keyframe(&self, tick: K) -> Option<&T> {
         self.curve.get_keyframe(tick)
     }
 
-    fn get_keyframe_mut(&mut self, tick: GameTick) -> Option<&mut T> {
+    fn get_keyframe_mut(&mut self, tick: K) -> Option<&mut T> {
         self.curve.get_keyframe_mut(tick)
     }
 
-    fn iter_future_curves(&self, tick: GameTick) -> Vec<(&GameTick, &T)> {
+    fn iter_future_curves<'a>(&'a self, tick: K) -> impl DoubleEndedIterator<Item = (&'a K, &'a T)>
+    where
+        T: 'a,
+    {
         self.curve.iter_future_curves(tick)
     }
 
-    fn next_keyframe(&self, tick: GameTick) -> Option<(&GameTick, &T)> {
+    fn next_keyframe(&self, tick: K) -> Option<(&K, &T)> {
         self.curve.next_keyframe(tick)
     }
 
-    fn iter_prev_curves(&self, tick: GameTick) -> Vec<(&GameTick, &T)> {
+    fn iter_prev_curves<'a>(&'a self, tick: K) -> impl DoubleEndedIterator<Item = (&'a K, &'a T)>
+    where
+        T: 'a,
+    {
         self.curve.iter_prev_curves(tick)
     }
 
-    fn prev_keyframe(&self, tick: GameTick) -> Option<(&GameTick, &T)> {
+    fn prev_keyframe(&self, tick: K) -> Option<(&K, &T)> {
         self.curve.prev_keyframe(tick)
     }
 
-    fn get_state(&self, tick: GameTick) -> Option<T> {
-        // we get the previous frame, if there is no previous frame then we try and get the current frame if we have one
-        // if there is no current frame then we return none.
-
-        let prev_frame = match self.prev_keyframe(tick) {
-            Some(data) => data,
-            None => match self.get_keyframe(tick) {
-                Some(data) => return Some(data.clone()),
-                None => return None,
-            },
-        };
+    fn get_state(&self, tick: K) -> Option<T> {
+        self.try_get_state(tick).ok()
+    }
 
-        let next_frame = match self.get_keyframe(tick) {
-            Some(data) => return Some(data.clone()),
-            None => match self.next_keyframe(tick) {
-                Some(data) => data,
-                None => return Some(prev_frame.1.clone()),
-            },
-        };
+    fn try_get_state(&self, tick: K) -> Result<T, CurveError<K>> {
+        let prev = self
+            .prev_keyframe(tick)
+            .ok_or(CurveError::NoKeyframesBefore { tick })?;
+        Self::interpolate(prev, self.curve.keyframe_after(tick), tick, 0.0)
+    }
 
-        let ratio =
-            (tick as f64 - *prev_frame.0 as f64) / (*next_frame.0 as f64 - *prev_frame.0 as f64);
-        Some(prev_frame.1.lerp(next_frame.1, ratio))
+    fn get_state_at(&self, tick: f64) -> Option<T> {
+        let (tick, fraction) = K::split_fractional(tick)?;
+        let prev = self.prev_keyframe(tick)?;
+        Self::interpolate(prev, self.curve.keyframe_after(tick), tick, fraction).ok()
     }
 
-    fn iter_future_curves_mut(&mut self, tick: GameTick) -> Vec<(&GameTick, &mut T)> {
+    fn iter_future_curves_mut<'a>(
+        &'a mut self,
+        tick: K,
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a mut T)>
+    where
+        T: 'a,
+    {
         self.curve.iter_future_curves_mut(tick)
     }
 
-    fn next_keyframe_mut(&mut self, tick: GameTick) -> Option<(&GameTick, &mut T)> {
+    fn next_keyframe_mut(&mut self, tick: K) -> Option<(&K, &mut T)> {
         self.curve.next_keyframe_mut(tick)
     }
 
-    fn iter_prev_curves_mut(&mut self, tick: GameTick) -> Vec<(&GameTick, &mut T)> {
+    fn iter_prev_curves_mut<'a>(
+        &'a mut self,
+        tick: K,
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a mut T)>
+    where
+        T: 'a,
+    {
         self.curve.iter_prev_curves_mut(tick)
     }
 
-    fn prev_keyframe_mut(&mut self, tick: GameTick) -> Option<(&GameTick, &mut T)> {
+    fn range<'a, R: RangeBounds<K>>(
+        &'a self,
+        range: R,
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a T)>
+    where
+        T: 'a,
+    {
+        self.curve.range(range)
+    }
+
+    fn prev_keyframe_mut(&mut self, tick: K) -> Option<(&K, &mut T)> {
         self.curve.prev_keyframe_mut(tick)
     }
 
-    fn remove_future_keyframes(&mut self, tick: &GameTick) {
+    fn first_keyframe(&self) -> Option<(&K, &T)> {
+        self.curve.first_keyframe()
+    }
+
+    fn last_keyframe(&self) -> Option<(&K, &T)> {
+        self.curve.last_keyframe()
+    }
+
+    fn remove_future_keyframes(&mut self, tick: &K) {
         self.curve.remove_future_keyframes(tick);
     }
 
-    fn remove_past_keyframes(&mut self, tick: &GameTick) {
+    fn remove_past_keyframes(&mut self, tick: &K) {
         self.curve.remove_past_keyframes(tick);
     }
+
+    fn remove_keyframes_before(&mut self, tick: &K) {
+        self.curve.remove_keyframes_before(tick);
+    }
+
+    fn frozen_tick(&self) -> Option<K> {
+        self.curve.frozen_tick()
+    }
+
+    fn set_frozen_tick(&mut self, tick: Option<K>) {
+        self.curve.set_frozen_tick(tick);
+    }
 }
 
-impl<T: LinearKeyframe<T>> LinearCurve<T> {}
+impl<T: LinearKeyframe<T>> LinearCurve<T> {
+    /// Creates a new [`LinearCurve`] using [`GameTick`]s. Use [`CurveTrait::new`] for curves using other [`Tick`] types
+    pub fn new() -> LinearCurve<T> {
+        CurveTrait::new()
+    }
+}
+
+impl<T: LinearKeyframe<T>, K: Tick, S: CurveStorage<K, T>> LinearCurve<T, K, S> {
+    /// Removes every keyframe that can be reproduced by interpolating between the keyframes around it, using the
+    /// Ramer-Douglas-Peucker algorithm. Returns how many keyframes were removed.
+    ///
+    /// ## Explanation:
+    ///
+    /// - The first and last keyframes are always kept.
+    /// - A keyframe is only removed if the state sampled on its tick afterwards is within `tolerance` of it, measured
+    ///   with the given [`KeyframeDistance`].
+    /// - This mutates the curve directly so no [`KeyframesRemoved`](crate::events::KeyframesRemoved) events are sent.
+    ///
+    /// ```rust,ignore
+    /// angle.simplify(0.01, |a: &BodyAngle, b: &BodyAngle| (a.angle - b.angle).abs() as f64);
+    /// ```
+    pub fn simplify(&mut self, tolerance: f64, metric: impl KeyframeDistance<T>) -> usize {
+        let ticks = self
+            .curve
+            .range(..)
+            .map(|(tick, _)| *tick)
+            .collect::<Vec<K>>();
+        if ticks.len() < 3 {
+            return 0;
+        }
+
+        let mut keep = vec![false; ticks.len()];
+        keep[0] = true;
+        keep[ticks.len() - 1] = true;
+        let mut segments = vec![(0, ticks.len() - 1)];
+        while let Some((start, end)) = segments.pop() {
+            if end - start < 2 {
+                continue;
+            }
+            let (Some(start_frame), Some(end_frame)) = (
+                self.curve.get_keyframe(ticks[start]),
+                self.curve.get_keyframe(ticks[end]),
+            ) else {
+                continue;
+            };
+            let span = ticks[start].distance(&ticks[end]);
+            let mut farthest = (start, 0.0);
+            for (index, tick) in ticks.iter().enumerate().take(end).skip(start + 1) {
+                let Some(keyframe) = self.curve.get_keyframe(*tick) else {
+                    continue;
+                };
+                let ratio = ticks[start].distance(tick) / span;
+                let error = metric.distance(&start_frame.lerp(end_frame, ratio), keyframe);
+                if error > farthest.1 {
+                    farthest = (index, error);
+                }
+            }
+            if farthest.1 > tolerance {
+                keep[farthest.0] = true;
+                segments.push((start, farthest.0));
+                segments.push((farthest.0, end));
+            }
+        }
+
+        let mut removed = 0;
+        for (tick, keep) in ticks.into_iter().zip(keep) {
+            if !keep {
+                self.curve.remove_keyframe(tick);
+                removed += 1;
+            }
+        }
+        removed
+    }
+
+    /// Inserts a keyframe and removes the previous last keyframe if it became redundant. Returns true if a keyframe
+    /// was removed.
+    ///
+    /// ## Explanation:
+    ///
+    /// - Only keyframes inserted after the last keyframe are simplified, otherwise this is the same as
+    ///   [`CurveTrait::insert_keyframe`].
+    /// - The previous last keyframe is removed if interpolating from the keyframe before it to the new keyframe gives
+    ///   a state within `tolerance` of it.
+    /// - Only the previous keyframe is checked, so on slowly drifting state the error from earlier removed keyframes
+    ///   can build up past the tolerance. Use [`LinearCurve::simplify`] when the tolerance must be strictly kept.
+    pub fn insert_keyframe_simplified(
+        &mut self,
+        tick: K,
+        keyframe: T,
+        tolerance: f64,
+        metric: impl KeyframeDistance<T>,
+    ) -> bool {
+        let redundant = match self.curve.last_keyframe() {
+            Some((last_tick, last)) if *last_tick < tick => {
+                match self.curve.keyframe_before(*last_tick) {
+                    Some((before_tick, before)) => {
+                        let ratio = before_tick.distance(last_tick) / before_tick.distance(&tick);
+                        let error = metric.distance(&before.lerp(&keyframe, ratio), last);
+                        (error <= tolerance).then_some(*last_tick)
+                    }
+                    None => None,
+                }
+            }
+            _ => None,
+        };
+        self.curve.insert_keyframe(tick, keyframe);
+        match redundant {
+            Some(last_tick) => {
+                self.curve.remove_keyframe(last_tick);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<T: LinearKeyframe<T>, K: Tick, S: CurveStorage<K, T>> Default for LinearCurve<T, K, S> {
+    fn default() -> Self {
+        CurveTrait::new()
+    }
+}
 
 /// A stepped curve containing all keyframes that implement [`SteppedKeyframe`]
 ///
@@ -302,53 +760,65 @@ impl<T: LinearKeyframe<T>> LinearCurve<T> {}
 /// until another keyframe is inserted. At that point the state following that keyframe will be that keyframe
 ///
 /// - State is the last keyframe before that [`GameTick`]
-#[derive(Component, Debug)]
+#[derive(Component, Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[cfg_attr(feature = "type_path", derive(TypePath))]
-pub struct SteppedCurve<T: SteppedKeyframe<T>> {
-    curve: Curve<T>,
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Curve, type_path = false, where T: FromReflect + TypePath, K: TypePath, S: TypePath))]
+pub struct SteppedCurve<
+    T: SteppedKeyframe<T>,
+    K: Tick = GameTick,
+    S: CurveStorage<K, T> = BTreeMap<K, T>,
+> {
+    pub(crate) curve: Curve<T, K, S>,
 }
 
-impl<T: SteppedKeyframe<T>> CurveTrait<T> for SteppedCurve<T> {
-    fn new() -> SteppedCurve<T> {
+impl<T: SteppedKeyframe<T>, K: Tick, S: CurveStorage<K, T>> CurveTrait<T, K>
+    for SteppedCurve<T, K, S>
+{
+    fn new() -> SteppedCurve<T, K, S> {
         SteppedCurve {
             curve: Curve::new(),
         }
     }
 
-    fn insert_keyframe(&mut self, tick: GameTick, keyframe: T) {
-        self.curve.insert_keyframe(tick, keyframe);
+    fn insert_keyframe(&mut self, tick: K, keyframe: T) -> Option<(K, T)> {
+        self.curve.insert_keyframe(tick, keyframe)
     }
 
-    fn remove_keyframe(&mut self, tick: GameTick) {
+    fn remove_keyframe(&mut self, tick: K) {
         self.curve.remove_keyframe(tick);
     }
 
-    fn get_keyframe(&self, tick: GameTick) -> Option<&T> {
+    fn get_keyframe(&self, tick: K) -> Option<&T> {
         self.curve.get_keyframe(tick)
     }
 
-    fn get_keyframe_mut(&mut self, tick: GameTick) -> Option<&mut T> {
+    fn get_keyframe_mut(&mut self, tick: K) -> Option<&mut T> {
         self.curve.get_keyframe_mut(tick)
     }
 
-    fn iter_future_curves(&self, tick: GameTick) -> Vec<(&GameTick, &T)> {
+    fn iter_future_curves<'a>(&'a self, tick: K) -> impl DoubleEndedIterator<Item = (&'a K, &'a T)>
+    where
+        T: 'a,
+    {
         self.curve.iter_future_curves(tick)
     }
 
-    fn next_keyframe(&self, tick: GameTick) -> Option<(&GameTick, &T)> {
+    fn next_keyframe(&self, tick: K) -> Option<(&K, &T)> {
         self.curve.next_keyframe(tick)
     }
 
-    fn iter_prev_curves(&self, tick: GameTick) -> Vec<(&GameTick, &T)> {
+    fn iter_prev_curves<'a>(&'a self, tick: K) -> impl DoubleEndedIterator<Item = (&'a K, &'a T)>
+    where
+        T: 'a,
+    {
         self.curve.iter_prev_curves(tick)
     }
 
-    fn prev_keyframe(&self, tick: GameTick) -> Option<(&GameTick, &T)> {
+    fn prev_keyframe(&self, tick: K) -> Option<(&K, &T)> {
         self.curve.prev_keyframe(tick)
     }
 
-    fn get_state(&self, tick: GameTick) -> Option<T> {
+    fn get_state(&self, tick: K) -> Option<T> {
         let data = match self.get_keyframe(tick) {
             Some(frame) => frame.clone(),
             None => match self.prev_keyframe(tick) {
@@ -359,29 +829,127 @@ impl<T: SteppedKeyframe<T>> CurveTrait<T> for SteppedCurve<T> {
         Some(data.clone())
     }
 
-    fn iter_future_curves_mut(&mut self, tick: GameTick) -> Vec<(&GameTick, &mut T)> {
+    fn try_get_state(&self, tick: K) -> Result<T, CurveError<K>> {
+        self.get_state(tick)
+            .ok_or(CurveError::NoKeyframesBefore { tick })
+    }
+
+    fn iter_future_curves_mut<'a>(
+        &'a mut self,
+        tick: K,
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a mut T)>
+    where
+        T: 'a,
+    {
         self.curve.iter_future_curves_mut(tick)
     }
 
-    fn next_keyframe_mut(&mut self, tick: GameTick) -> Option<(&GameTick, &mut T)> {
+    fn next_keyframe_mut(&mut self, tick: K) -> Option<(&K, &mut T)> {
         self.curve.next_keyframe_mut(tick)
     }
 
-    fn iter_prev_curves_mut(&mut self, tick: GameTick) -> Vec<(&GameTick, &mut T)> {
+    fn iter_prev_curves_mut<'a>(
+        &'a mut self,
+        tick: K,
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a mut T)>
+    where
+        T: 'a,
+    {
         self.curve.iter_prev_curves_mut(tick)
     }
 
-    fn prev_keyframe_mut(&mut self, tick: GameTick) -> Option<(&GameTick, &mut T)> {
+    fn range<'a, R: RangeBounds<K>>(
+        &'a self,
+        range: R,
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a T)>
+    where
+        T: 'a,
+    {
+        self.curve.range(range)
+    }
+
+    fn prev_keyframe_mut(&mut self, tick: K) -> Option<(&K, &mut T)> {
         self.curve.prev_keyframe_mut(tick)
     }
 
-    fn remove_future_keyframes(&mut self, tick: &GameTick) {
+    fn first_keyframe(&self) -> Option<(&K, &T)> {
+        self.curve.first_keyframe()
+    }
+
+    fn last_keyframe(&self) -> Option<(&K, &T)> {
+        self.curve.last_keyframe()
+    }
+
+    fn remove_future_keyframes(&mut self, tick: &K) {
         self.curve.remove_future_keyframes(tick);
     }
 
-    fn remove_past_keyframes(&mut self, tick: &GameTick) {
+    fn remove_past_keyframes(&mut self, tick: &K) {
         self.curve.remove_past_keyframes(tick);
     }
+
+    fn remove_keyframes_before(&mut self, tick: &K) {
+        self.curve.remove_keyframes_before(tick);
+    }
+
+    fn frozen_tick(&self) -> Option<K> {
+        self.curve.frozen_tick()
+    }
+
+    fn set_frozen_tick(&mut self, tick: Option<K>) {
+        self.curve.set_frozen_tick(tick);
+    }
+}
+
+impl<T: SteppedKeyframe<T>> SteppedCurve<T> {
+    /// Creates a new [`SteppedCurve`] using [`GameTick`]s. Use [`CurveTrait::new`] for curves using other [`Tick`] types
+    pub fn new() -> SteppedCurve<T> {
+        CurveTrait::new()
+    }
+}
+
+impl<T: SteppedKeyframe<T> + PartialEq, K: Tick, S: CurveStorage<K, T>> SteppedCurve<T, K, S> {
+    /// Inserts the keyframe only if it changes the state of the curve on the given tick. Returns true if the keyframe
+    /// was inserted.
+    ///
+    /// A keyframe equal to the state already on that tick, whether from a keyframe on the tick or an earlier one, is
+    /// redundant since every tick would have the same state with or without it.
+    pub fn insert_if_changed(&mut self, tick: K, keyframe: T) -> bool {
+        if self
+            .curve
+            .prev_keyframe(tick)
+            .is_some_and(|(_, state)| *state == keyframe)
+        {
+            return false;
+        }
+        self.curve.insert_keyframe(tick, keyframe);
+        true
+    }
+
+    /// Removes every keyframe that is equal to the keyframe before it, since they don't change the state of the
+    /// curve. Returns how many keyframes were removed.
+    pub fn dedup(&mut self) -> usize {
+        let mut previous: Option<&T> = None;
+        let mut redundant = Vec::new();
+        for (tick, keyframe) in self.curve.range(..) {
+            if previous.is_some_and(|previous| previous == keyframe) {
+                redundant.push(*tick);
+            } else {
+                previous = Some(keyframe);
+            }
+        }
+        let removed = redundant.len();
+        for tick in redundant {
+            self.curve.remove_keyframe(tick);
+        }
+        removed
+    }
+}
+
+impl<T: SteppedKeyframe<T>, K: Tick, S: CurveStorage<K, T>> Default for SteppedCurve<T, K, S> {
+    fn default() -> Self {
+        CurveTrait::new()
+    }
 }
 
 /// A pulse curve containing all keyframes that implement [`PulseKeyframe`]
@@ -391,86 +959,578 @@ impl<T: SteppedKeyframe<T>> CurveTrait<T> for SteppedCurve<T> {
 /// State in a Pulse curve is instantanious. State is only valid on the [`GameTick`] that it exists on.
 ///
 /// - State only exists on the [`GameTick`] that it was saved under
-#[derive(Component, Debug)]
+#[derive(Component, Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[cfg_attr(feature = "type_path", derive(TypePath))]
-pub struct PulseCurve<T: PulseKeyframe<T>> {
-    curve: Curve<T>,
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Curve, type_path = false, where T: FromReflect + TypePath, K: TypePath, S: TypePath))]
+pub struct PulseCurve<
+    T: PulseKeyframe<T>,
+    K: Tick = GameTick,
+    S: CurveStorage<K, T> = BTreeMap<K, T>,
+> {
+    pub(crate) curve: Curve<T, K, S>,
 }
 
-impl<T: PulseKeyframe<T>> CurveTrait<T> for PulseCurve<T> {
-    fn new() -> PulseCurve<T> {
+impl<T: PulseKeyframe<T>, K: Tick, S: CurveStorage<K, T>> CurveTrait<T, K> for PulseCurve<T, K, S> {
+    fn new() -> PulseCurve<T, K, S> {
         PulseCurve {
             curve: Curve::new(),
         }
     }
 
-    fn insert_keyframe(&mut self, tick: GameTick, keyframe: T) {
-        self.curve.insert_keyframe(tick, keyframe);
+    fn insert_keyframe(&mut self, tick: K, keyframe: T) -> Option<(K, T)> {
+        self.curve.insert_keyframe(tick, keyframe)
     }
 
-    fn remove_keyframe(&mut self, tick: GameTick) {
+    fn remove_keyframe(&mut self, tick: K) {
         self.curve.remove_keyframe(tick);
     }
 
-    fn get_keyframe(&self, tick: GameTick) -> Option<&T> {
+    fn get_keyframe(&self, tick: K) -> Option<&T> {
         self.curve.get_keyframe(tick)
     }
 
-    fn get_keyframe_mut(&mut self, tick: GameTick) -> Option<&mut T> {
+    fn get_keyframe_mut(&mut self, tick: K) -> Option<&mut T> {
         self.curve.get_keyframe_mut(tick)
     }
 
-    fn iter_future_curves(&self, tick: GameTick) -> Vec<(&GameTick, &T)> {
+    fn iter_future_curves<'a>(&'a self, tick: K) -> impl DoubleEndedIterator<Item = (&'a K, &'a T)>
+    where
+        T: 'a,
+    {
         self.curve.iter_future_curves(tick)
     }
 
-    fn next_keyframe(&self, tick: GameTick) -> Option<(&GameTick, &T)> {
+    fn next_keyframe(&self, tick: K) -> Option<(&K, &T)> {
         self.curve.next_keyframe(tick)
     }
 
-    fn iter_prev_curves(&self, tick: GameTick) -> Vec<(&GameTick, &T)> {
+    fn iter_prev_curves<'a>(&'a self, tick: K) -> impl DoubleEndedIterator<Item = (&'a K, &'a T)>
+    where
+        T: 'a,
+    {
         self.curve.iter_prev_curves(tick)
     }
 
-    fn prev_keyframe(&self, tick: GameTick) -> Option<(&GameTick, &T)> {
+    fn prev_keyframe(&self, tick: K) -> Option<(&K, &T)> {
         self.curve.prev_keyframe(tick)
     }
 
-    fn get_state(&self, tick: GameTick) -> Option<T> {
+    fn get_state(&self, tick: K) -> Option<T> {
         self.get_keyframe(tick).cloned()
     }
 
-    fn iter_future_curves_mut(&mut self, tick: GameTick) -> Vec<(&GameTick, &mut T)> {
+    fn try_get_state(&self, tick: K) -> Result<T, CurveError<K>> {
+        self.get_state(tick)
+            .ok_or(CurveError::NoKeyframesBefore { tick })
+    }
+
+    fn iter_future_curves_mut<'a>(
+        &'a mut self,
+        tick: K,
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a mut T)>
+    where
+        T: 'a,
+    {
         self.curve.iter_future_curves_mut(tick)
     }
 
-    fn next_keyframe_mut(&mut self, tick: GameTick) -> Option<(&GameTick, &mut T)> {
+    fn next_keyframe_mut(&mut self, tick: K) -> Option<(&K, &mut T)> {
         self.curve.next_keyframe_mut(tick)
     }
 
-    fn iter_prev_curves_mut(&mut self, tick: GameTick) -> Vec<(&GameTick, &mut T)> {
+    fn iter_prev_curves_mut<'a>(
+        &'a mut self,
+        tick: K,
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a mut T)>
+    where
+        T: 'a,
+    {
         self.curve.iter_prev_curves_mut(tick)
     }
 
-    fn prev_keyframe_mut(&mut self, tick: GameTick) -> Option<(&GameTick, &mut T)> {
+    fn range<'a, R: RangeBounds<K>>(
+        &'a self,
+        range: R,
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a T)>
+    where
+        T: 'a,
+    {
+        self.curve.range(range)
+    }
+
+    fn prev_keyframe_mut(&mut self, tick: K) -> Option<(&K, &mut T)> {
         self.curve.prev_keyframe_mut(tick)
     }
 
-    fn remove_future_keyframes(&mut self, tick: &GameTick) {
+    fn first_keyframe(&self) -> Option<(&K, &T)> {
+        self.curve.first_keyframe()
+    }
+
+    fn last_keyframe(&self) -> Option<(&K, &T)> {
+        self.curve.last_keyframe()
+    }
+
+    fn remove_future_keyframes(&mut self, tick: &K) {
         self.curve.remove_future_keyframes(tick);
     }
 
-    fn remove_past_keyframes(&mut self, tick: &GameTick) {
+    fn remove_past_keyframes(&mut self, tick: &K) {
         self.curve.remove_past_keyframes(tick);
     }
+
+    fn remove_keyframes_before(&mut self, tick: &K) {
+        self.curve.remove_keyframes_before(tick);
+    }
+
+    fn frozen_tick(&self) -> Option<K> {
+        self.curve.frozen_tick()
+    }
+
+    fn set_frozen_tick(&mut self, tick: Option<K>) {
+        self.curve.set_frozen_tick(tick);
+    }
+}
+
+impl<T: PulseKeyframe<T>> PulseCurve<T> {
+    /// Creates a new [`PulseCurve`] using [`GameTick`]s. Use [`CurveTrait::new`] for curves using other [`Tick`] types
+    pub fn new() -> PulseCurve<T> {
+        CurveTrait::new()
+    }
+}
+
+impl<T: PulseKeyframe<T>, K: Tick, S: CurveStorage<K, T>> Default for PulseCurve<T, K, S> {
+    fn default() -> Self {
+        CurveTrait::new()
+    }
+}
+
+/// A cubic curve containing all keyframes that implement [`CubicKeyframe`]
+///
+/// ## Explanation:
+///
+/// State in a cubic curve is derived via a cubic Hermite interpolation between the last keyframe and the next keyframe.
+/// This produces smooth motion through every keyframe so far fewer keyframes are needed than with a [`LinearCurve`].
+///
+/// - If a request for state falls exactly on a keyframe than that keyframe is simply returned.
+/// - If there are no future keyframes then the last keyframe is used.
+/// - If there are no past keyframes then no state is returned.
+/// - Otherwise the returned state is interpolated using the tangents of the two keyframes. Keyframes that don't provide
+///   a tangent use a Catmull-Rom tangent derived from the keyframes on either side of them.
+#[derive(Component, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Curve, type_path = false, where T: FromReflect + TypePath, K: TypePath, S: TypePath))]
+pub struct CubicCurve<
+    T: CubicKeyframe<T>,
+    K: Tick = GameTick,
+    S: CurveStorage<K, T> = BTreeMap<K, T>,
+> {
+    pub(crate) curve: Curve<T, K, S>,
+}
+
+impl<T: CubicKeyframe<T>, K: Tick, S: CurveStorage<K, T>> CurveTrait<T, K> for CubicCurve<T, K, S> {
+    fn new() -> CubicCurve<T, K, S> {
+        CubicCurve {
+            curve: Curve::new(),
+        }
+    }
+
+    fn insert_keyframe(&mut self, tick: K, keyframe: T) -> Option<(K, T)> {
+        self.curve.insert_keyframe(tick, keyframe)
+    }
+
+    fn remove_keyframe(&mut self, tick: K) {
+        self.curve.remove_keyframe(tick);
+    }
+
+    fn get_keyframe(&self, tick: K) -> Option<&T> {
+        self.curve.get_keyframe(tick)
+    }
+
+    fn get_keyframe_mut(&mut self, tick: K) -> Option<&mut T> {
+        self.curve.get_keyframe_mut(tick)
+    }
+
+    fn iter_future_curves<'a>(&'a self, tick: K) -> impl DoubleEndedIterator<Item = (&'a K, &'a T)>
+    where
+        T: 'a,
+    {
+        self.curve.iter_future_curves(tick)
+    }
+
+    fn next_keyframe(&self, tick: K) -> Option<(&K, &T)> {
+        self.curve.next_keyframe(tick)
+    }
+
+    fn iter_prev_curves<'a>(&'a self, tick: K) -> impl DoubleEndedIterator<Item = (&'a K, &'a T)>
+    where
+        T: 'a,
+    {
+        self.curve.iter_prev_curves(tick)
+    }
+
+    fn prev_keyframe(&self, tick: K) -> Option<(&K, &T)> {
+        self.curve.prev_keyframe(tick)
+    }
+
+    fn get_state(&self, tick: K) -> Option<T> {
+        self.sample(tick, 0.0).ok()
+    }
+
+    fn try_get_state(&self, tick: K) -> Result<T, CurveError<K>> {
+        self.sample(tick, 0.0)
+    }
+
+    fn get_state_at(&self, tick: f64) -> Option<T> {
+        let (tick, fraction) = K::split_fractional(tick)?;
+        self.sample(tick, fraction).ok()
+    }
+
+    fn iter_future_curves_mut<'a>(
+        &'a mut self,
+        tick: K,
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a mut T)>
+    where
+        T: 'a,
+    {
+        self.curve.iter_future_curves_mut(tick)
+    }
+
+    fn next_keyframe_mut(&mut self, tick: K) -> Option<(&K, &mut T)> {
+        self.curve.next_keyframe_mut(tick)
+    }
+
+    fn iter_prev_curves_mut<'a>(
+        &'a mut self,
+        tick: K,
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a mut T)>
+    where
+        T: 'a,
+    {
+        self.curve.iter_prev_curves_mut(tick)
+    }
+
+    fn range<'a, R: RangeBounds<K>>(
+        &'a self,
+        range: R,
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a T)>
+    where
+        T: 'a,
+    {
+        self.curve.range(range)
+    }
+
+    fn prev_keyframe_mut(&mut self, tick: K) -> Option<(&K, &mut T)> {
+        self.curve.prev_keyframe_mut(tick)
+    }
+
+    fn first_keyframe(&self) -> Option<(&K, &T)> {
+        self.curve.first_keyframe()
+    }
+
+    fn last_keyframe(&self) -> Option<(&K, &T)> {
+        self.curve.last_keyframe()
+    }
+
+    fn remove_future_keyframes(&mut self, tick: &K) {
+        self.curve.remove_future_keyframes(tick);
+    }
+
+    fn remove_past_keyframes(&mut self, tick: &K) {
+        self.curve.remove_past_keyframes(tick);
+    }
+
+    fn remove_keyframes_before(&mut self, tick: &K) {
+        self.curve.remove_keyframes_before(tick);
+    }
+
+    fn frozen_tick(&self) -> Option<K> {
+        self.curve.frozen_tick()
+    }
+
+    fn set_frozen_tick(&mut self, tick: Option<K>) {
+        self.curve.set_frozen_tick(tick);
+    }
+}
+
+impl<T: CubicKeyframe<T>> CubicCurve<T> {
+    /// Creates a new [`CubicCurve`] using [`GameTick`]s. Use [`CurveTrait::new`] for curves using other [`Tick`] types
+    pub fn new() -> CubicCurve<T> {
+        CurveTrait::new()
+    }
+}
+
+impl<T: CubicKeyframe<T>, K: Tick, S: CurveStorage<K, T>> Default for CubicCurve<T, K, S> {
+    fn default() -> Self {
+        CurveTrait::new()
+    }
+}
+
+impl<T: CubicKeyframe<T>, K: Tick, S: CurveStorage<K, T>> CubicCurve<T, K, S> {
+    /// Samples the curve `fraction` of a tick after the given tick
+    fn sample(&self, tick: K, fraction: f64) -> Result<T, CurveError<K>> {
+        if fraction == 0.0 {
+            if let Some(frame) = self.get_keyframe(tick) {
+                return Ok(frame.clone());
+            }
+        }
+
+        let (start_tick, start) = self
+            .prev_keyframe(tick)
+            .ok_or(CurveError::NoKeyframesBefore { tick })?;
+        let (end_tick, end) = match self.curve.keyframe_after(tick) {
+            Some(data) => data,
+            None => return Ok(start.clone()),
+        };
+
+        let span = start_tick.distance(end_tick);
+        let ratio = (start_tick.distance(&tick) + fraction) / span;
+        if !ratio.is_finite() {
+            return Err(CurveError::InvalidInterpolation {
+                prev: *start_tick,
+                next: *end_tick,
+                ratio,
+            });
+        }
+
+        let start_tangent = self.tangent(
+            (*start_tick, start),
+            self.curve.keyframe_before(*start_tick),
+            Some((end_tick, end)),
+        );
+        let end_tangent = self.tangent(
+            (*end_tick, end),
+            Some((start_tick, start)),
+            self.curve.keyframe_after(*end_tick),
+        );
+
+        // Hermite basis functions. The start point basis is folded into the difference between the two points
+        let ratio2 = ratio * ratio;
+        let ratio3 = ratio2 * ratio;
+        let h01 = -2.0 * ratio3 + 3.0 * ratio2;
+        let h10 = ratio3 - 2.0 * ratio2 + ratio;
+        let h11 = ratio3 - ratio2;
+
+        let difference = end.add_scaled(start, -1.0);
+        Ok(start
+            .add_scaled(&difference, h01)
+            .add_scaled(&start_tangent.0, start_tangent.1 * span * h10)
+            .add_scaled(&end_tangent.0, end_tangent.1 * span * h11))
+    }
+
+    /// Returns the tangent of the keyframe at `tick` as a direction and the factor it has to be scaled by to get the
+    /// rate of change per tick.
+    ///
+    /// Uses the keyframes own tangent if it has one, otherwise a Catmull-Rom tangent is derived from the keyframes
+    /// before and after it. At the ends of the curve the keyframe itself takes the place of the missing neighbour.
+    fn tangent(
+        &self,
+        keyframe: (K, &T),
+        before: Option<(&K, &T)>,
+        after: Option<(&K, &T)>,
+    ) -> (T, f64) {
+        if let Some(tangent) = keyframe.1.tangent() {
+            return (tangent, 1.0);
+        }
+        let (before_tick, before) = before.map_or(keyframe, |(tick, state)| (*tick, state));
+        let (after_tick, after) = after.map_or(keyframe, |(tick, state)| (*tick, state));
+
+        (
+            after.add_scaled(before, -1.0),
+            1.0 / before_tick.distance(&after_tick),
+        )
+    }
+}
+
+/// A physics curve containing all keyframes that implement [`PhysicsKeyframe`]
+///
+/// ## Explanation:
+///
+/// Keyframes in a physics curve store their own derivatives, such as velocity and acceleration. State is extrapolated
+/// from the last keyframe so a projectile only needs a new keyframe whenever its trajectory changes.
+///
+/// - If a request for state falls exactly on a keyframe than that keyframe is simply returned.
+/// - If there are no past keyframes then no state is returned.
+/// - Otherwise the state is extrapolated from the last keyframe, including past the final keyframe.
+#[derive(Component, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Curve, type_path = false, where T: FromReflect + TypePath, K: TypePath, S: TypePath))]
+pub struct PhysicsCurve<
+    T: PhysicsKeyframe<T>,
+    K: Tick = GameTick,
+    S: CurveStorage<K, T> = BTreeMap<K, T>,
+> {
+    pub(crate) curve: Curve<T, K, S>,
+}
+
+impl<T: PhysicsKeyframe<T>, K: Tick, S: CurveStorage<K, T>> CurveTrait<T, K>
+    for PhysicsCurve<T, K, S>
+{
+    fn new() -> PhysicsCurve<T, K, S> {
+        PhysicsCurve {
+            curve: Curve::new(),
+        }
+    }
+
+    fn insert_keyframe(&mut self, tick: K, keyframe: T) -> Option<(K, T)> {
+        self.curve.insert_keyframe(tick, keyframe)
+    }
+
+    fn remove_keyframe(&mut self, tick: K) {
+        self.curve.remove_keyframe(tick);
+    }
+
+    fn get_keyframe(&self, tick: K) -> Option<&T> {
+        self.curve.get_keyframe(tick)
+    }
+
+    fn get_keyframe_mut(&mut self, tick: K) -> Option<&mut T> {
+        self.curve.get_keyframe_mut(tick)
+    }
+
+    fn iter_future_curves<'a>(&'a self, tick: K) -> impl DoubleEndedIterator<Item = (&'a K, &'a T)>
+    where
+        T: 'a,
+    {
+        self.curve.iter_future_curves(tick)
+    }
+
+    fn next_keyframe(&self, tick: K) -> Option<(&K, &T)> {
+        self.curve.next_keyframe(tick)
+    }
+
+    fn iter_prev_curves<'a>(&'a self, tick: K) -> impl DoubleEndedIterator<Item = (&'a K, &'a T)>
+    where
+        T: 'a,
+    {
+        self.curve.iter_prev_curves(tick)
+    }
+
+    fn prev_keyframe(&self, tick: K) -> Option<(&K, &T)> {
+        self.curve.prev_keyframe(tick)
+    }
+
+    fn get_state(&self, tick: K) -> Option<T> {
+        self.sample(tick, 0.0).ok()
+    }
+
+    fn try_get_state(&self, tick: K) -> Result<T, CurveError<K>> {
+        self.sample(tick, 0.0)
+    }
+
+    fn get_state_at(&self, tick: f64) -> Option<T> {
+        let (tick, fraction) = K::split_fractional(tick)?;
+        self.sample(tick, fraction).ok()
+    }
+
+    fn iter_future_curves_mut<'a>(
+        &'a mut self,
+        tick: K,
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a mut T)>
+    where
+        T: 'a,
+    {
+        self.curve.iter_future_curves_mut(tick)
+    }
+
+    fn next_keyframe_mut(&mut self, tick: K) -> Option<(&K, &mut T)> {
+        self.curve.next_keyframe_mut(tick)
+    }
+
+    fn iter_prev_curves_mut<'a>(
+        &'a mut self,
+        tick: K,
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a mut T)>
+    where
+        T: 'a,
+    {
+        self.curve.iter_prev_curves_mut(tick)
+    }
+
+    fn range<'a, R: RangeBounds<K>>(
+        &'a self,
+        range: R,
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a T)>
+    where
+        T: 'a,
+    {
+        self.curve.range(range)
+    }
+
+    fn prev_keyframe_mut(&mut self, tick: K) -> Option<(&K, &mut T)> {
+        self.curve.prev_keyframe_mut(tick)
+    }
+
+    fn first_keyframe(&self) -> Option<(&K, &T)> {
+        self.curve.first_keyframe()
+    }
+
+    fn last_keyframe(&self) -> Option<(&K, &T)> {
+        self.curve.last_keyframe()
+    }
+
+    fn remove_future_keyframes(&mut self, tick: &K) {
+        self.curve.remove_future_keyframes(tick);
+    }
+
+    fn remove_past_keyframes(&mut self, tick: &K) {
+        self.curve.remove_past_keyframes(tick);
+    }
+
+    fn remove_keyframes_before(&mut self, tick: &K) {
+        self.curve.remove_keyframes_before(tick);
+    }
+
+    fn frozen_tick(&self) -> Option<K> {
+        self.curve.frozen_tick()
+    }
+
+    fn set_frozen_tick(&mut self, tick: Option<K>) {
+        self.curve.set_frozen_tick(tick);
+    }
+}
+
+impl<T: PhysicsKeyframe<T>, K: Tick, S: CurveStorage<K, T>> PhysicsCurve<T, K, S> {
+    /// Extrapolates the state `fraction` of a tick after the given tick from the keyframe on or before it
+    fn sample(&self, tick: K, fraction: f64) -> Result<T, CurveError<K>> {
+        if fraction == 0.0 {
+            if let Some(frame) = self.get_keyframe(tick) {
+                return Ok(frame.clone());
+            }
+        }
+
+        let (prev_tick, prev_frame) = self
+            .prev_keyframe(tick)
+            .ok_or(CurveError::NoKeyframesBefore { tick })?;
+        let elapsed = prev_tick.distance(&tick) + fraction;
+        if !elapsed.is_finite() {
+            return Err(CurveError::InvalidExtrapolation {
+                prev: *prev_tick,
+                tick,
+                elapsed,
+            });
+        }
+        Ok(prev_frame.extrapolate(elapsed))
+    }
+}
+
+impl<T: PhysicsKeyframe<T>> PhysicsCurve<T> {
+    /// Creates a new [`PhysicsCurve`] using [`GameTick`]s. Use [`CurveTrait::new`] for curves using other [`Tick`] types
+    pub fn new() -> PhysicsCurve<T> {
+        CurveTrait::new()
+    }
+}
+
+impl<T: PhysicsKeyframe<T>, K: Tick, S: CurveStorage<K, T>> Default for PhysicsCurve<T, K, S> {
+    fn default() -> Self {
+        CurveTrait::new()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use bevy::reflect::TypePath;
 
-    use crate::prelude::SteppedKeyframe;
+    use crate::prelude::{CubicKeyframe, CurveTrait, LinearKeyframe, SteppedKeyframe};
 
     #[derive(Clone, TypePath)]
     struct Foo;
@@ -481,6 +1541,157 @@ mod tests {
 
     impl SteppedKeyframe<Bar> for Bar {}
 
+    #[derive(Clone, Debug, PartialEq)]
+    struct Position(f64, Option<f64>);
+
+    impl CubicKeyframe<Position> for Position {
+        fn add_scaled(&self, other: &Position, factor: f64) -> Position {
+            Position(self.0 + other.0 * factor, None)
+        }
+
+        fn tangent(&self) -> Option<Position> {
+            self.1.map(|tangent| Position(tangent, None))
+        }
+    }
+
+    #[test]
+    fn test_cubic_curve() {
+        use super::CubicCurve;
+
+        // Catmull-Rom tangents on evenly moving keyframes reproduce the linear motion
+        let mut curve = CubicCurve::new();
+        curve.insert_keyframe(0, Position(0.0, None));
+        curve.insert_keyframe(10, Position(10.0, None));
+        curve.insert_keyframe(20, Position(20.0, None));
+        assert_eq!(curve.get_state(5), Some(Position(5.0, None)));
+        assert_eq!(curve.get_state(15), Some(Position(15.0, None)));
+        assert_eq!(curve.get_state(25), Some(Position(20.0, None)));
+
+        // Flat tangents ease in and out of every keyframe
+        let mut curve = CubicCurve::new();
+        curve.insert_keyframe(0, Position(0.0, Some(0.0)));
+        curve.insert_keyframe(10, Position(10.0, Some(0.0)));
+        assert_eq!(curve.get_state(5), Some(Position(5.0, None)));
+        let eased = curve.get_state(2).unwrap().0;
+        assert!(eased < 2.0 && eased > 0.0);
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Radius(f64);
+
+    impl LinearKeyframe<Radius> for Radius {
+        fn lerp(&self, next_frame_state: &Radius, ratio: f64) -> Radius {
+            Radius(self.0 + (next_frame_state.0 - self.0) * ratio)
+        }
+    }
+
+    #[test]
+    fn test_fractional_sampling() {
+        use super::{LinearCurve, SteppedCurve};
+
+        let mut linear = LinearCurve::new();
+        linear.insert_keyframe(10, Radius(0.0));
+        linear.insert_keyframe(12, Radius(1.0));
+        assert_eq!(linear.get_state_at(9.5), None);
+        assert_eq!(linear.get_state_at(10.0), Some(Radius(0.0)));
+        assert_eq!(linear.get_state_at(10.5), Some(Radius(0.25)));
+        assert_eq!(linear.get_state_at(11.5), Some(Radius(0.75)));
+        assert_eq!(linear.get_state_at(12.5), Some(Radius(1.0)));
+        assert_eq!(linear.get_state_at(-1.0), None);
+
+        let mut stepped = SteppedCurve::new();
+        stepped.insert_keyframe(10, Foo);
+        assert!(stepped.get_state_at(9.9).is_none());
+        assert!(stepped.get_state_at(10.9).is_some());
+    }
+
+    #[test]
+    fn test_range_iterators() {
+        use std::ops::Bound;
+
+        use super::LinearCurve;
+
+        let mut linear = LinearCurve::new();
+        for tick in 0..10 {
+            linear.insert_keyframe(tick * 10, Radius(tick as f64));
+        }
+        let ticks = |iter: &mut dyn Iterator<Item = (&u64, &Radius)>| {
+            iter.map(|(tick, _)| *tick).collect::<Vec<u64>>()
+        };
+        assert_eq!(ticks(&mut linear.range(20..40)), vec![20, 30]);
+        assert_eq!(ticks(&mut linear.range(75..)), vec![80, 90]);
+        assert!(linear
+            .range((Bound::Included(30), Bound::Included(10)))
+            .next()
+            .is_none());
+        assert!(linear
+            .range((Bound::Excluded(20), Bound::Excluded(20)))
+            .next()
+            .is_none());
+        assert_eq!(
+            ticks(&mut linear.iter_prev_curves(20).rev()),
+            vec![20, 10, 0]
+        );
+        assert_eq!(
+            linear.iter_future_curves(45).next_back(),
+            Some((&90, &Radius(9.0)))
+        );
+
+        for (_, radius) in linear.iter_future_curves_mut(50) {
+            radius.0 = 0.0;
+        }
+        assert_eq!(linear.get_state(90), Some(Radius(0.0)));
+    }
+
+    #[test]
+    fn test_custom_tick() {
+        use super::LinearCurve;
+        use crate::prelude::SecondsTick;
+
+        let mut linear = LinearCurve::<Radius, SecondsTick>::new();
+        linear.insert_keyframe(SecondsTick(0.5), Radius(0.0));
+        linear.insert_keyframe(SecondsTick(1.5), Radius(1.0));
+        assert_eq!(linear.get_state(SecondsTick(0.75)), Some(Radius(0.25)));
+        assert_eq!(linear.get_state_at(1.25), Some(Radius(0.75)));
+    }
+
+    #[test]
+    fn test_physics_curve() {
+        use super::PhysicsCurve;
+        use crate::prelude::Ballistic;
+
+        let mut curve = PhysicsCurve::new();
+        assert_eq!(curve.get_state(0), None);
+
+        curve.insert_keyframe(
+            10,
+            Ballistic {
+                position: 0.0,
+                velocity: 2.0,
+                acceleration: -1.0,
+            },
+        );
+        assert_eq!(curve.get_state(5), None);
+        assert_eq!(
+            curve.get_state(14),
+            Some(Ballistic {
+                position: 0.0,
+                velocity: -2.0,
+                acceleration: -1.0,
+            })
+        );
+
+        curve.insert_keyframe(
+            20,
+            Ballistic {
+                position: 5.0,
+                velocity: 1.0,
+                acceleration: 0.0,
+            },
+        );
+        assert_eq!(curve.get_state(30).map(|state| state.position), Some(15.0));
+    }
+
     #[cfg(feature = "type_path")]
     #[test]
     fn test_type_path_equivilancy() {
@@ -491,4 +1702,171 @@ mod tests {
             SteppedCurve::<Bar>::type_path()
         )
     }
+
+    #[cfg(feature = "reflect")]
+    #[test]
+    fn test_reflect_type_path() {
+        use bevy::reflect::{DynamicTypePath, Reflect};
+
+        use super::SteppedCurve;
+
+        #[derive(Clone, Reflect)]
+        struct Baz;
+
+        impl SteppedKeyframe<Baz> for Baz {}
+
+        // Reflected curves use the same type path as curves of keyframes that only implement `TypePath`
+        let curve = SteppedCurve::<Baz>::new();
+        assert_eq!(curve.reflect_type_path(), SteppedCurve::<Baz>::type_path());
+        assert_eq!(
+            SteppedCurve::<Baz>::type_path(),
+            SteppedCurve::<Foo>::type_path().replace("Foo", "Baz")
+        );
+    }
+
+    #[test]
+    fn test_simplify() {
+        let metric = |a: &Radius, b: &Radius| (a.0 - b.0).abs();
+
+        // A straight line with a single bump in the middle
+        let mut curve = crate::prelude::LinearCurve::new();
+        for tick in 0..=20u64 {
+            let bump = if tick == 10 { 5.0 } else { 0.0 };
+            curve.insert_keyframe(tick, Radius(tick as f64 + bump));
+        }
+        assert_eq!(curve.simplify(0.1, metric), 16);
+        assert_eq!(
+            curve.range(..).map(|(tick, _)| *tick).collect::<Vec<u64>>(),
+            vec![0, 9, 10, 11, 20]
+        );
+        assert_eq!(curve.get_state(5), Some(Radius(5.0)));
+        assert_eq!(curve.simplify(0.1, metric), 0);
+
+        let mut curve = crate::prelude::LinearCurve::new();
+        assert!(!curve.insert_keyframe_simplified(0, Radius(0.0), 0.1, metric));
+        assert!(!curve.insert_keyframe_simplified(1, Radius(1.0), 0.1, metric));
+        assert!(curve.insert_keyframe_simplified(2, Radius(2.0), 0.1, metric));
+        assert!(curve.insert_keyframe_simplified(3, Radius(3.0), 0.1, metric));
+        assert!(!curve.insert_keyframe_simplified(4, Radius(0.0), 0.1, metric));
+        assert_eq!(
+            curve.range(..).map(|(tick, _)| *tick).collect::<Vec<u64>>(),
+            vec![0, 3, 4]
+        );
+    }
+
+    #[test]
+    fn test_stepped_dedup() {
+        let mut curve = crate::prelude::SteppedCurve::new();
+        assert!(curve.insert_if_changed(0, 1u32));
+        assert!(!curve.insert_if_changed(5, 1));
+        assert!(curve.insert_if_changed(10, 2));
+        assert!(!curve.insert_if_changed(10, 2));
+        assert!(!curve.insert_if_changed(15, 2));
+        assert_eq!(curve.range(..).count(), 2);
+
+        curve.insert_keyframe(20, 2);
+        curve.insert_keyframe(25, 3);
+        curve.insert_keyframe(30, 3);
+        curve.insert_keyframe(35, 3);
+        curve.insert_keyframe(40, 2);
+        assert_eq!(curve.dedup(), 3);
+        assert_eq!(
+            curve.range(..).map(|(tick, _)| *tick).collect::<Vec<u64>>(),
+            vec![0, 10, 25, 40]
+        );
+        assert_eq!(curve.get_state(37), Some(3));
+        assert_eq!(curve.dedup(), 0);
+    }
+
+    #[test]
+    fn test_fallible_api() {
+        use crate::prelude::{CurveError, SecondsTick, StateLifetime};
+
+        let mut curve = crate::prelude::SteppedCurve::new();
+        assert_eq!(
+            curve.try_get_state(5),
+            Err(CurveError::NoKeyframesBefore { tick: 5 })
+        );
+        curve.insert_keyframe(10, 1u32);
+        curve.freeze_history(20);
+        assert_eq!(curve.frozen_tick(), Some(20));
+        assert_eq!(
+            curve.try_insert_keyframe(15, 2),
+            Err(CurveError::InsertBeforeFrozenTick {
+                tick: 15,
+                frozen: 20
+            })
+        );
+        assert_eq!(
+            curve.try_remove_keyframe(10),
+            Err(CurveError::InsertBeforeFrozenTick {
+                tick: 10,
+                frozen: 20
+            })
+        );
+        assert!(curve.try_remove_future_keyframes(&5).is_err());
+        assert_eq!(curve.try_insert_keyframe(20, 2), Ok(None));
+        assert_eq!(curve.try_get_state(25), Ok(2));
+
+        // The infallible methods ignore the frozen tick
+        curve.insert_keyframe(15, 3);
+        assert_eq!(curve.get_state(15), Some(3));
+        curve.set_frozen_tick(None);
+        assert_eq!(curve.try_remove_keyframe(15), Ok(()));
+
+        let lifetime = StateLifetime {
+            spawned: 10,
+            despawned: Some(30),
+        };
+        assert_eq!(lifetime.try_get_state(&curve, 20), Ok(2));
+        assert_eq!(
+            lifetime.try_get_state(&curve, 30),
+            Err(CurveError::TickOutsideLifetime { tick: 30 })
+        );
+
+        // Keyframes so far apart that the distance between them overflows to infinity, so the ratio is infinity over
+        // infinity
+        let (start, end, tick) = (
+            SecondsTick(-f64::MAX),
+            SecondsTick(f64::MAX),
+            SecondsTick(f64::MAX / 2.0),
+        );
+        let mut linear = crate::prelude::LinearCurve::<Radius, SecondsTick>::new();
+        linear.insert_keyframe(start, Radius(0.0));
+        linear.insert_keyframe(end, Radius(1.0));
+        assert!(matches!(
+            linear.try_get_state(tick),
+            Err(CurveError::InvalidInterpolation { ratio, .. }) if ratio.is_nan()
+        ));
+        assert_eq!(linear.get_state(tick), None);
+        assert_eq!(linear.get_state_at(tick.0), None);
+        assert_eq!(linear.get_state(end), Some(Radius(1.0)));
+
+        let mut cubic = crate::prelude::CubicCurve::<Position, SecondsTick>::default();
+        cubic.insert_keyframe(start, Position(0.0, None));
+        cubic.insert_keyframe(end, Position(1.0, None));
+        assert!(matches!(
+            cubic.try_get_state(tick),
+            Err(CurveError::InvalidInterpolation { ratio, .. }) if ratio.is_nan()
+        ));
+        assert_eq!(cubic.get_state_at(tick.0), None);
+
+        let ballistic = crate::prelude::Ballistic {
+            position: 0.0f32,
+            velocity: 1.0,
+            acceleration: 0.0,
+        };
+        let mut physics = crate::prelude::PhysicsCurve::<_, SecondsTick>::default();
+        physics.insert_keyframe(start, ballistic);
+        assert_eq!(
+            physics.try_get_state(end),
+            Err(CurveError::InvalidExtrapolation {
+                prev: start,
+                tick: end,
+                elapsed: f64::INFINITY
+            })
+        );
+        assert_eq!(physics.get_state(tick), None);
+        assert_eq!(physics.try_get_state(start), Ok(ballistic));
+    }
 }
@@ -0,0 +1,245 @@
+use std::fmt::{Display, Formatter};
+
+use crate::{tick::Tick, GameTick};
+
+/// The reason a fallible curve operation, such as [`CurveTrait::try_get_state`](crate::curves::CurveTrait::try_get_state)
+/// or [`CurveTrait::try_insert_keyframe`](crate::curves::CurveTrait::try_insert_keyframe), failed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CurveError<K: Tick = GameTick> {
+    /// There is no keyframe that the state on the tick can be taken from. For most curves this means there is no
+    /// keyframe on or before the tick, for a [`PulseCurve`](crate::curves::PulseCurve) there is no keyframe on it.
+    NoKeyframesBefore {
+        /// The tick that was sampled
+        tick: K,
+    },
+    /// The tick is outside of the entities [`StateLifetime`](crate::lifetime::StateLifetime) so it has no state
+    TickOutsideLifetime {
+        /// The tick that was sampled
+        tick: K,
+    },
+    /// The keyframe is before the curve's frozen tick, see [`CurveTrait::freeze_history`](crate::curves::CurveTrait::freeze_history).
+    /// Returned when inserting or removing keyframes.
+    InsertBeforeFrozenTick {
+        /// The tick that was edited
+        tick: K,
+        /// The tick that history is frozen before
+        frozen: K,
+    },
+    /// Interpolating between the keyframes on either side of the tick gave a ratio that isn't a finite number, usually
+    /// because the two keyframes have no distance between them.
+    InvalidInterpolation {
+        /// The keyframe before the tick
+        prev: K,
+        /// The keyframe after the tick
+        next: K,
+        /// The ratio that the interpolation produced
+        ratio: f64,
+    },
+    /// Extrapolating from the keyframe before the tick, as a [`PhysicsCurve`](crate::curves::PhysicsCurve) does, gave
+    /// a time since the keyframe that isn't a finite number, usually because the ticks are too far apart to measure.
+    InvalidExtrapolation {
+        /// The keyframe before the tick
+        prev: K,
+        /// The tick that was sampled
+        tick: K,
+        /// The ticks between the keyframe and the sampled tick
+        elapsed: f64,
+    },
+}
+
+impl<K: Tick> Display for CurveError<K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CurveError::NoKeyframesBefore { tick } => {
+                write!(f, "no keyframe to take the state on tick {tick:?} from")
+            }
+            CurveError::TickOutsideLifetime { tick } => {
+                write!(f, "tick {tick:?} is outside of the entities lifetime")
+            }
+            CurveError::InsertBeforeFrozenTick { tick, frozen } => write!(
+                f,
+                "cannot edit the keyframe on tick {tick:?} since history is frozen before tick {frozen:?}"
+            ),
+            CurveError::InvalidInterpolation { prev, next, ratio } => write!(
+                f,
+                "interpolating between the keyframes on tick {prev:?} and {next:?} gave an invalid ratio of {ratio}"
+            ),
+            CurveError::InvalidExtrapolation {
+                prev,
+                tick,
+                elapsed,
+            } => write!(
+                f,
+                "extrapolating from the keyframe on tick {prev:?} to tick {tick:?} gave an invalid distance of {elapsed}"
+            ),
+        }
+    }
+}
+
+impl<K: Tick> std::error::Error for CurveError<K> {}
+
+/// Returns [`CurveError::InsertBeforeFrozenTick`] if the tick is before the frozen tick
+pub(crate) fn check_frozen<K: Tick>(frozen: Option<K>, tick: K) -> Result<(), CurveError<K>> {
+    match frozen {
+        Some(frozen) if tick < frozen => Err(CurveError::InsertBeforeFrozenTick { tick, frozen }),
+        _ => Ok(()),
+    }
+}
+
+/// The reason reading or writing a binary timeline file failed. See [`TimelineReader`](crate::format::TimelineReader)
+#[derive(Debug)]
+pub enum FormatError {
+    /// Reading from or writing to the underlying stream failed
+    Io(std::io::Error),
+    /// The file doesn't start with [`TIMELINE_MAGIC`](crate::format::TIMELINE_MAGIC) so isn't a timeline file
+    InvalidMagic,
+    /// The file was written with a newer format version than this version of the crate can read
+    UnsupportedVersion {
+        /// The format version of the file
+        version: u32,
+    },
+    /// A curve record was decoded as a different keyframe type than it was written with
+    TypeMismatch {
+        /// The type name of the keyframe type that the record was decoded as
+        expected: String,
+        /// The type name the record was written with
+        found: String,
+    },
+    /// A keyframe was written with a version of its encoding that its [`KeyframeCodec`](crate::format::KeyframeCodec)
+    /// doesn't know how to decode
+    UnsupportedKeyframeVersion {
+        /// The type name of the keyframe type
+        type_name: String,
+        /// The version of the keyframe encoding in the file
+        version: u32,
+    },
+    /// The data is corrupt or was written out of order
+    InvalidData(&'static str),
+}
+
+impl Display for FormatError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormatError::Io(error) => write!(f, "io error: {error}"),
+            FormatError::InvalidMagic => write!(f, "not a timeline file"),
+            FormatError::UnsupportedVersion { version } => {
+                write!(f, "unsupported timeline format version {version}")
+            }
+            FormatError::TypeMismatch { expected, found } => write!(
+                f,
+                "expected keyframes of type {expected} but the curve was written with {found}"
+            ),
+            FormatError::UnsupportedKeyframeVersion { type_name, version } => write!(
+                f,
+                "unsupported version {version} of the {type_name} keyframe encoding"
+            ),
+            FormatError::InvalidData(reason) => write!(f, "invalid timeline data: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FormatError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for FormatError {
+    fn from(error: std::io::Error) -> Self {
+        FormatError::Io(error)
+    }
+}
+
+/// The reason [`ReflectCurve::insert_keyframe`](crate::reflect::ReflectCurve::insert_keyframe) failed. Ticks are
+/// given as `f64`, see [`Tick::as_f64`]
+#[cfg(feature = "reflect")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReflectCurveError {
+    /// The curve isn't the curve type that the [`ReflectCurve`](crate::reflect::ReflectCurve) was created for
+    CurveTypeMismatch,
+    /// The keyframe isn't the curves keyframe type
+    KeyframeTypeMismatch,
+    /// The tick isn't a whole tick of the curves [`Tick`] type
+    InvalidTick {
+        /// The tick that was edited
+        tick: f64,
+    },
+    /// The keyframe is before the curve's frozen tick, see [`CurveError::InsertBeforeFrozenTick`]
+    InsertBeforeFrozenTick {
+        /// The tick that was edited
+        tick: f64,
+        /// The tick that history is frozen before
+        frozen: f64,
+    },
+}
+
+#[cfg(feature = "reflect")]
+impl Display for ReflectCurveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReflectCurveError::CurveTypeMismatch => {
+                write!(f, "the curve isn't the type the reflected curve data is for")
+            }
+            ReflectCurveError::KeyframeTypeMismatch => {
+                write!(f, "the keyframe isn't the curves keyframe type")
+            }
+            ReflectCurveError::InvalidTick { tick } => {
+                write!(f, "{tick} isn't a valid tick of the curve")
+            }
+            ReflectCurveError::InsertBeforeFrozenTick { tick, frozen } => write!(
+                f,
+                "cannot edit the keyframe on tick {tick} since history is frozen before tick {frozen}"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "reflect")]
+impl std::error::Error for ReflectCurveError {}
+
+/// The reason loading a [`CurveAsset`](crate::asset::CurveAsset) failed
+#[cfg(feature = "asset")]
+#[derive(Debug)]
+pub enum CurveAssetError {
+    /// Reading the file failed
+    Io(std::io::Error),
+    /// The file isn't valid RON or doesn't match the keyframe type
+    Ron(bevy::asset::ron::error::SpannedError),
+}
+
+#[cfg(feature = "asset")]
+impl Display for CurveAssetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CurveAssetError::Io(error) => write!(f, "io error: {error}"),
+            CurveAssetError::Ron(error) => write!(f, "invalid curve asset: {error}"),
+        }
+    }
+}
+
+#[cfg(feature = "asset")]
+impl std::error::Error for CurveAssetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CurveAssetError::Io(error) => Some(error),
+            CurveAssetError::Ron(error) => Some(error),
+        }
+    }
+}
+
+#[cfg(feature = "asset")]
+impl From<std::io::Error> for CurveAssetError {
+    fn from(error: std::io::Error) -> Self {
+        CurveAssetError::Io(error)
+    }
+}
+
+#[cfg(feature = "asset")]
+impl From<bevy::asset::ron::error::SpannedError> for CurveAssetError {
+    fn from(error: bevy::asset::ron::error::SpannedError) -> Self {
+        CurveAssetError::Ron(error)
+    }
+}
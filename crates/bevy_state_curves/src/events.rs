@@ -0,0 +1,392 @@
+use std::{
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+};
+
+use bevy::{
+    app::App,
+    ecs::system::SystemParam,
+    prelude::{Commands, Entity, Event, EventWriter, Query, World},
+};
+
+use crate::{
+    plugin::ComponentCurve,
+    replication::{CurveChangeLog, PatchOp},
+    GameTick,
+};
+
+/// Sent and triggered on the entity whenever a keyframe of type `T` is inserted through [`CurveMut`].
+///
+/// Read it with an [`EventReader`](bevy::prelude::EventReader) or observe it on the entity with an observer.
+#[derive(Event, Debug)]
+pub struct KeyframeInserted<T> {
+    /// The entity that the curve is on
+    pub entity: Entity,
+    /// The tick that the keyframe was inserted on
+    pub tick: GameTick,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> KeyframeInserted<T> {
+    /// Creates a new [`KeyframeInserted`] event
+    pub fn new(entity: Entity, tick: GameTick) -> KeyframeInserted<T> {
+        KeyframeInserted {
+            entity,
+            tick,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for KeyframeInserted<T> {
+    fn clone(&self) -> Self {
+        KeyframeInserted::new(self.entity, self.tick)
+    }
+}
+
+/// Sent and triggered on the entity whenever keyframes of type `T` are removed through [`CurveMut`] or by a
+/// [`RewindTo`](crate::rewind::RewindTo).
+///
+/// Only sent if at least one keyframe was actually removed. `ticks` is the range of ticks that was cleared. Also sent
+/// for the single tick of a keyframe that a bounded storage, such as [`BoundedStorage`](crate::storage::BoundedStorage),
+/// dropped to make room for an inserted keyframe.
+#[derive(Event, Debug)]
+pub struct KeyframesRemoved<T> {
+    /// The entity that the curve is on
+    pub entity: Entity,
+    /// The range of ticks that keyframes were removed from
+    pub ticks: (Bound<GameTick>, Bound<GameTick>),
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> KeyframesRemoved<T> {
+    /// Creates a new [`KeyframesRemoved`] event
+    pub fn new(entity: Entity, ticks: (Bound<GameTick>, Bound<GameTick>)) -> KeyframesRemoved<T> {
+        KeyframesRemoved {
+            entity,
+            ticks,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns true if the given tick was within the removed range
+    pub fn contains(&self, tick: GameTick) -> bool {
+        self.ticks.contains(&tick)
+    }
+}
+
+impl<T> Clone for KeyframesRemoved<T> {
+    fn clone(&self) -> Self {
+        KeyframesRemoved::new(self.entity, self.ticks)
+    }
+}
+
+/// Adds the [`KeyframeInserted`] and [`KeyframesRemoved`] events for keyframes of type `T`, which must exist for
+/// [`CurveMut`] to be used
+pub(crate) fn add_keyframe_events<T: Send + Sync + 'static>(app: &mut App) {
+    app.add_event::<KeyframeInserted<T>>();
+    app.add_event::<KeyframesRemoved<T>>();
+}
+
+/// A [`SystemParam`] for mutating curves of type `C` that reports every change as a [`KeyframeInserted`] or
+/// [`KeyframesRemoved`] event.
+///
+/// Every change is also recorded into the [`CurveChangeLog`] of the entity, if it has one, at the moment it is made.
+///
+/// Mutating the curve component directly still works but doesn't send any events.
+///
+/// Edits ignore the curve's [frozen tick](crate::curves::CurveTrait::freeze_history), check
+/// [`CurveTrait::frozen_tick`](crate::curves::CurveTrait::frozen_tick) on [`CurveMut::get`] before editing history
+/// that must stay frozen.
+///
+/// ```rust,ignore
+/// fn simulate(mut angles: CurveMut<LinearCurve<BodyAngle>>, bodies: Query<Entity, With<Body>>) {
+///     for entity in bodies.iter() {
+///         angles.insert_keyframe(entity, 10, BodyAngle { angle: 1.0 });
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct CurveMut<'w, 's, C: ComponentCurve> {
+    curves: Query<
+        'w,
+        's,
+        (
+            &'static mut C,
+            Option<&'static mut CurveChangeLog<<C as ComponentCurve>::State>>,
+        ),
+    >,
+    inserted: EventWriter<'w, KeyframeInserted<<C as ComponentCurve>::State>>,
+    removed: EventWriter<'w, KeyframesRemoved<<C as ComponentCurve>::State>>,
+    commands: Commands<'w, 's>,
+}
+
+impl<C: ComponentCurve> CurveMut<'_, '_, C> {
+    /// Returns the curve on the given entity
+    pub fn get(&self, entity: Entity) -> Option<&C> {
+        self.curves.get(entity).ok().map(|(curve, _)| curve)
+    }
+
+    /// Inserts a keyframe into the curve on the entity. Returns false if the entity doesn't have the curve.
+    ///
+    /// If the curve's storage drops its oldest keyframe to make room, a [`KeyframesRemoved`] is sent for it. If the
+    /// storage doesn't keep the keyframe at all nothing is sent.
+    pub fn insert_keyframe(&mut self, entity: Entity, tick: GameTick, keyframe: C::State) -> bool {
+        let Ok((mut curve, mut change_log)) = self.curves.get_mut(entity) else {
+            return false;
+        };
+        let logged = change_log.is_some().then(|| keyframe.clone());
+        let evicted = curve.insert_keyframe(tick, keyframe).map(|(tick, _)| tick);
+        if evicted == Some(tick) {
+            return true;
+        }
+        if let (Some(change_log), Some(keyframe)) = (change_log.as_mut(), logged) {
+            change_log.record(PatchOp::Insert { tick, keyframe });
+        }
+        let event = KeyframeInserted::new(entity, tick);
+        self.inserted.send(event.clone());
+        self.commands.trigger_targets(event, entity);
+
+        if let Some(evicted) = evicted {
+            let ticks = (Bound::Included(evicted), Bound::Included(evicted));
+            if let Some(change_log) = change_log.as_mut() {
+                change_log.record(PatchOp::Remove { ticks });
+            }
+            let event = KeyframesRemoved::new(entity, ticks);
+            self.removed.send(event.clone());
+            self.commands.trigger_targets(event, entity);
+        }
+        true
+    }
+
+    /// Removes the keyframe on the given tick from the curve on the entity. Returns false if the entity doesn't have the curve
+    pub fn remove_keyframe(&mut self, entity: Entity, tick: GameTick) -> bool {
+        self.remove_range(
+            entity,
+            (Bound::Included(tick), Bound::Included(tick)),
+            |curve| curve.remove_keyframe(tick),
+        )
+    }
+
+    /// Removes every keyframe on or after the given tick from the curve on the entity. Returns false if the entity doesn't have the curve
+    pub fn remove_future_keyframes(&mut self, entity: Entity, tick: GameTick) -> bool {
+        self.remove_range(entity, (Bound::Included(tick), Bound::Unbounded), |curve| {
+            curve.remove_future_keyframes(&tick)
+        })
+    }
+
+    /// Removes every keyframe on or before the given tick from the curve on the entity. Returns false if the entity doesn't have the curve
+    pub fn remove_past_keyframes(&mut self, entity: Entity, tick: GameTick) -> bool {
+        self.remove_range(entity, (Bound::Unbounded, Bound::Included(tick)), |curve| {
+            curve.remove_past_keyframes(&tick)
+        })
+    }
+
+    /// Removes every keyframe before the given tick except the last one on or before it from the curve on the entity,
+    /// see [`CurveTrait::remove_keyframes_before`](crate::curves::CurveTrait::remove_keyframes_before). Returns false
+    /// if the entity doesn't have the curve
+    pub fn remove_keyframes_before(&mut self, entity: Entity, tick: GameTick) -> bool {
+        let Ok((curve, _)) = self.curves.get(entity) else {
+            return false;
+        };
+        let Some((kept, _)) = curve.prev_keyframe(tick) else {
+            return true;
+        };
+        let kept = *kept;
+        self.remove_range(entity, (Bound::Unbounded, Bound::Excluded(kept)), |curve| {
+            curve.remove_keyframes_before(&tick)
+        })
+    }
+
+    /// Removes the keyframes in `ticks` using `remove` and sends a [`KeyframesRemoved`] if there were any
+    fn remove_range(
+        &mut self,
+        entity: Entity,
+        ticks: (Bound<GameTick>, Bound<GameTick>),
+        remove: impl FnOnce(&mut C),
+    ) -> bool {
+        let Ok((mut curve, change_log)) = self.curves.get_mut(entity) else {
+            return false;
+        };
+        if curve.range(ticks).next().is_none() {
+            return true;
+        }
+        if let Some(mut change_log) = change_log {
+            change_log.record(PatchOp::Remove { ticks });
+        }
+        remove(&mut curve);
+        let event = KeyframesRemoved::new(entity, ticks);
+        self.removed.send(event.clone());
+        self.commands.trigger_targets(event, entity);
+        true
+    }
+}
+
+/// Sends and triggers a [`KeyframesRemoved`] event directly on the world and records the removal into the entities
+/// [`CurveChangeLog`], used by the commands that mutate curves.
+pub(crate) fn send_keyframes_removed<T: Clone + Send + Sync + 'static>(
+    world: &mut World,
+    entity: Entity,
+    ticks: (Bound<GameTick>, Bound<GameTick>),
+) {
+    if let Some(mut change_log) = world.get_mut::<CurveChangeLog<T>>(entity) {
+        change_log.record(PatchOp::Remove { ticks });
+    }
+    let event = KeyframesRemoved::<T>::new(entity, ticks);
+    world.send_event(event.clone());
+    world.trigger_targets(event, entity);
+}
+
+/// Sends and triggers a [`KeyframeInserted`] event directly on the world and records the inserted keyframe into the
+/// entities [`CurveChangeLog`], used by the commands that mutate curves.
+pub(crate) fn send_keyframe_inserted<T: Clone + Send + Sync + 'static>(
+    world: &mut World,
+    entity: Entity,
+    tick: GameTick,
+    keyframe: T,
+) {
+    if let Some(mut change_log) = world.get_mut::<CurveChangeLog<T>>(entity) {
+        change_log.record(PatchOp::Insert { tick, keyframe });
+    }
+    let event = KeyframeInserted::<T>::new(entity, tick);
+    world.send_event(event.clone());
+    world.trigger_targets(event, entity);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+
+    use bevy::prelude::{
+        App, Component, Entity, EventReader, Local, ResMut, Resource, Trigger, Update,
+    };
+
+    use super::{CurveMut, KeyframeInserted, KeyframesRemoved};
+    use crate::prelude::{
+        BoundedStorage, CurveChangeLog, CurvePatch, CurveTrait, CurvesPlugin, GameTick,
+        RegisterCurve, ReplicateCurve, SteppedCurve, SteppedKeyframe,
+    };
+
+    #[derive(Component, Clone, Debug, PartialEq)]
+    struct Health(u32);
+
+    impl SteppedKeyframe<Health> for Health {}
+
+    #[derive(Resource, Default)]
+    struct Seen {
+        inserted: Vec<u64>,
+        removed: Vec<(Bound<u64>, Bound<u64>)>,
+        observed: usize,
+    }
+
+    #[test]
+    fn test_keyframe_events() {
+        let mut app = App::new();
+        app.add_plugins(CurvesPlugin);
+        app.register_curve::<SteppedCurve<Health>>();
+        app.init_resource::<Seen>();
+
+        let entity = app.world_mut().spawn(SteppedCurve::<Health>::new()).id();
+        app.world_mut().entity_mut(entity).observe(
+            |_trigger: Trigger<KeyframeInserted<Health>>, mut seen: ResMut<Seen>| {
+                seen.observed += 1;
+            },
+        );
+
+        app.add_systems(
+            Update,
+            move |mut curves: CurveMut<SteppedCurve<Health>>, mut ran: Local<bool>| {
+                if *ran {
+                    return;
+                }
+                *ran = true;
+                curves.insert_keyframe(entity, 0, Health(10));
+                curves.insert_keyframe(entity, 10, Health(5));
+                curves.insert_keyframe(entity, 20, Health(0));
+                curves.remove_future_keyframes(entity, 15);
+                // Nothing left to remove so no event is sent
+                curves.remove_future_keyframes(entity, 15);
+                assert!(!curves.insert_keyframe(Entity::PLACEHOLDER, 0, Health(1)));
+            },
+        );
+        app.add_systems(
+            Update,
+            |mut seen: ResMut<Seen>,
+             mut inserted: EventReader<KeyframeInserted<Health>>,
+             mut removed: EventReader<KeyframesRemoved<Health>>| {
+                seen.inserted
+                    .extend(inserted.read().map(|event| event.tick));
+                seen.removed.extend(removed.read().map(|event| event.ticks));
+            },
+        );
+
+        app.update();
+        app.update();
+
+        let seen = app.world().resource::<Seen>();
+        assert_eq!(seen.inserted, vec![0, 10, 20]);
+        assert_eq!(seen.removed, vec![(Bound::Included(15), Bound::Unbounded)]);
+        assert_eq!(seen.observed, 3);
+    }
+
+    type BoundedHealth = SteppedCurve<Health, GameTick, BoundedStorage<GameTick, Health, 2>>;
+
+    #[test]
+    fn test_bounded_storage_events() {
+        let mut app = App::new();
+        app.add_plugins(CurvesPlugin);
+        app.replicate_curve::<BoundedHealth>();
+        app.init_resource::<Seen>();
+
+        let entity = app.world_mut().spawn(BoundedHealth::new()).id();
+        app.add_systems(
+            Update,
+            move |mut curves: CurveMut<BoundedHealth>, mut ran: Local<bool>| {
+                if *ran {
+                    return;
+                }
+                *ran = true;
+                curves.insert_keyframe(entity, 10, Health(10));
+                curves.insert_keyframe(entity, 20, Health(5));
+                // Drops the keyframe on tick 10 to make room
+                curves.insert_keyframe(entity, 30, Health(0));
+                // Older than every stored keyframe so it isn't stored
+                curves.insert_keyframe(entity, 0, Health(1));
+            },
+        );
+        app.add_systems(
+            Update,
+            |mut seen: ResMut<Seen>,
+             mut inserted: EventReader<KeyframeInserted<Health>>,
+             mut removed: EventReader<KeyframesRemoved<Health>>| {
+                seen.inserted
+                    .extend(inserted.read().map(|event| event.tick));
+                seen.removed.extend(removed.read().map(|event| event.ticks));
+            },
+        );
+
+        app.update();
+        app.update();
+
+        let seen = app.world().resource::<Seen>();
+        assert_eq!(seen.inserted, vec![10, 20, 30]);
+        assert_eq!(
+            seen.removed,
+            vec![(Bound::Included(10), Bound::Included(10))]
+        );
+
+        // A client following the change log ends up with the same keyframes as the bounded curve
+        let mut client = SteppedCurve::<Health>::new();
+        let patch: CurvePatch<Health> = app
+            .world()
+            .get::<CurveChangeLog<Health>>(entity)
+            .unwrap()
+            .patch_since(0)
+            .unwrap();
+        assert!(patch.apply(&mut client, &mut 0));
+        assert_eq!(
+            client.range(..).map(|(tick, _)| *tick).collect::<Vec<_>>(),
+            vec![20, 30]
+        );
+    }
+}
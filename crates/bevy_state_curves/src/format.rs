@@ -0,0 +1,971 @@
+use std::{
+    io::{ErrorKind, Read, Write},
+    ops::Bound,
+};
+
+use bevy::math::{DVec2, DVec3, DVec4, Quat, Vec2, Vec3, Vec4};
+
+use crate::{
+    curves::CurveTrait, error::FormatError, keyframe_trait::Ballistic, lifetime::StateLifetime,
+    storage::is_valid_range, GameTick,
+};
+
+/// The bytes every timeline file starts with
+pub const TIMELINE_MAGIC: [u8; 4] = *b"BSCT";
+
+/// The version of the timeline format written by [`TimelineWriter`]. [`TimelineReader`] reads every version up to
+/// and including this one.
+///
+/// - Version 1 holds curve records.
+/// - Version 2 adds lifetime and removal records.
+pub const TIMELINE_FORMAT_VERSION: u32 = 2;
+
+const RECORD_END: u8 = 0;
+const RECORD_CURVE: u8 = 1;
+const RECORD_LIFETIME: u8 = 2;
+const RECORD_REMOVAL: u8 = 3;
+
+/// Encodes and decodes a keyframe type for the binary timeline format written by [`TimelineWriter`].
+///
+/// ## Explanation:
+///
+/// - [`KeyframeCodec::type_name`] is written with every curve and checked when it is decoded. It must stay the same
+///   for as long as old files should be readable, so don't use [`std::any::type_name`].
+/// - [`KeyframeCodec::VERSION`] is written with every curve and given back to [`KeyframeCodec::decode`]. Bump it
+///   whenever the encoding changes and keep decoding the old versions so old files can still be loaded.
+/// - Implemented for the numeric primitives, `bool`, `String`, the glam vectors, `Quat` and [`Ballistic`]. Structs
+///   usually encode each of their fields in order.
+///
+/// ```rust,ignore
+/// impl KeyframeCodec for BodyOrbit {
+///     const VERSION: u32 = 1;
+///
+///     fn type_name() -> String {
+///         "solar_system::BodyOrbit".into()
+///     }
+///
+///     fn encode(&self, buf: &mut Vec<u8>) {
+///         self.radius.encode(buf);
+///         self.speed.encode(buf);
+///     }
+///
+///     fn decode(version: u32, bytes: &mut &[u8]) -> Result<Self, FormatError> {
+///         Ok(BodyOrbit {
+///             radius: f32::decode(version, bytes)?,
+///             // Version 0 had no speed
+///             speed: if version == 0 { 1.0 } else { f32::decode(version, bytes)? },
+///         })
+///     }
+/// }
+/// ```
+pub trait KeyframeCodec: Sized {
+    /// The version of the encoding written by [`KeyframeCodec::encode`]
+    const VERSION: u32 = 0;
+
+    /// A stable name identifying the keyframe type in the file
+    fn type_name() -> String;
+
+    /// Appends the encoded keyframe to the buffer
+    fn encode(&self, buf: &mut Vec<u8>);
+
+    /// Decodes a keyframe written with the given version of the encoding from the front of the bytes, advancing
+    /// them past it
+    fn decode(version: u32, bytes: &mut &[u8]) -> Result<Self, FormatError>;
+}
+
+/// Appends the value to the buffer as a LEB128 varint, using one byte for values below 128
+pub fn encode_varint(mut value: u64, buf: &mut Vec<u8>) {
+    while value >= 0x80 {
+        buf.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+/// Decodes a varint written by [`encode_varint`] from the front of the bytes, advancing them past it
+pub fn decode_varint(bytes: &mut &[u8]) -> Result<u64, FormatError> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = bytes
+            .split_first()
+            .ok_or(FormatError::InvalidData("truncated varint"))?;
+        *bytes = rest;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(FormatError::InvalidData("varint is too long"))
+}
+
+/// Reads a varint directly from the stream
+fn read_varint(reader: &mut impl Read) -> Result<u64, FormatError> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0];
+        reader.read_exact(&mut byte)?;
+        value |= u64::from(byte[0] & 0x7f) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(FormatError::InvalidData("varint is too long"))
+}
+
+/// Splits the first `N` bytes off the front of the bytes
+fn decode_array<const N: usize>(bytes: &mut &[u8]) -> Result<[u8; N], FormatError> {
+    if bytes.len() < N {
+        return Err(FormatError::InvalidData("truncated keyframe"));
+    }
+    let (array, rest) = bytes.split_at(N);
+    *bytes = rest;
+    Ok(array.try_into().unwrap())
+}
+
+/// Appends the type name to the buffer, prefixed with its length
+fn encode_type_name(type_name: &str, buf: &mut Vec<u8>) {
+    encode_varint(type_name.len() as u64, buf);
+    buf.extend_from_slice(type_name.as_bytes());
+}
+
+/// Decodes a type name written by [`encode_type_name`] from the front of the bytes, advancing them past it
+fn decode_type_name(bytes: &mut &[u8]) -> Result<String, FormatError> {
+    let len = decode_varint(bytes)? as usize;
+    if bytes.len() < len {
+        return Err(FormatError::InvalidData("truncated type name"));
+    }
+    let (type_name, rest) = bytes.split_at(len);
+    *bytes = rest;
+    String::from_utf8(type_name.to_vec())
+        .map_err(|_| FormatError::InvalidData("type name isn't utf8"))
+}
+
+/// Appends a range bound to the buffer as a tag, followed by the tick if it is bounded
+fn encode_bound(bound: Bound<GameTick>, buf: &mut Vec<u8>) {
+    match bound {
+        Bound::Unbounded => buf.push(0),
+        Bound::Included(tick) => {
+            buf.push(1);
+            encode_varint(tick, buf);
+        }
+        Bound::Excluded(tick) => {
+            buf.push(2);
+            encode_varint(tick, buf);
+        }
+    }
+}
+
+/// Decodes a bound written by [`encode_bound`] from the front of the bytes, advancing them past it
+fn decode_bound(bytes: &mut &[u8]) -> Result<Bound<GameTick>, FormatError> {
+    let [tag] = decode_array(bytes)?;
+    match tag {
+        0 => Ok(Bound::Unbounded),
+        1 => Ok(Bound::Included(decode_varint(bytes)?)),
+        2 => Ok(Bound::Excluded(decode_varint(bytes)?)),
+        _ => Err(FormatError::InvalidData("invalid range bound")),
+    }
+}
+
+/// Writes curves into the binary timeline format, one curve at a time.
+///
+/// ## Explanation:
+///
+/// - The file starts with [`TIMELINE_MAGIC`] and the [`TIMELINE_FORMAT_VERSION`], followed by a record for every
+///   written curve, [`StateLifetime`] and removal of keyframes.
+/// - Each curve record holds the curves id, the [`KeyframeCodec::type_name`] and [`KeyframeCodec::VERSION`] of its
+///   keyframes, and its keyframes. Ticks are stored as varint deltas from the previous keyframe so a keyframe every
+///   tick costs a single byte for its tick.
+/// - The id is anything that identifies the curve to the reader, such as the bits of the
+///   [`Entity`](bevy::prelude::Entity) it belongs to. The same id can be written more than once, for example to stream
+///   a curves new keyframes into the file while a match is running.
+/// - Records are written straight to the writer, wrap it in a [`BufWriter`](std::io::BufWriter) when writing to a file.
+///
+/// ```rust,ignore
+/// let mut writer = TimelineWriter::new(BufWriter::new(File::create("match.timeline")?))?;
+/// for (entity, curve) in curves.iter() {
+///     writer.write_curve(entity.to_bits(), curve)?;
+/// }
+/// writer.finish()?;
+/// ```
+pub struct TimelineWriter<W: Write> {
+    writer: W,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> TimelineWriter<W> {
+    /// Creates a new writer, writing the file header to the writer
+    pub fn new(mut writer: W) -> Result<TimelineWriter<W>, FormatError> {
+        let mut buffer = TIMELINE_MAGIC.to_vec();
+        encode_varint(u64::from(TIMELINE_FORMAT_VERSION), &mut buffer);
+        writer.write_all(&buffer)?;
+        buffer.clear();
+        Ok(TimelineWriter { writer, buffer })
+    }
+
+    /// Writes every keyframe in the curve as a record with the given id
+    pub fn write_curve<T: KeyframeCodec>(
+        &mut self,
+        id: u64,
+        curve: &impl CurveTrait<T>,
+    ) -> Result<(), FormatError> {
+        self.write_keyframes(id, curve.range(..))
+    }
+
+    /// Writes the keyframes as a curve record with the given id. The keyframes must be ordered by tick, like the
+    /// iterators returned by [`CurveTrait::range`] and [`CurveTrait::iter_future_curves`]
+    pub fn write_keyframes<'a, T: KeyframeCodec + 'a>(
+        &mut self,
+        id: u64,
+        keyframes: impl IntoIterator<Item = (&'a GameTick, &'a T)>,
+    ) -> Result<(), FormatError> {
+        self.write_named_keyframes(id, &T::type_name(), keyframes)
+    }
+
+    /// Writes the keyframes as a curve record with the given id under the type name instead of the
+    /// [`KeyframeCodec::type_name`] of `T`
+    pub(crate) fn write_named_keyframes<'a, T: KeyframeCodec + 'a>(
+        &mut self,
+        id: u64,
+        type_name: &str,
+        keyframes: impl IntoIterator<Item = (&'a GameTick, &'a T)>,
+    ) -> Result<(), FormatError> {
+        let mut body = std::mem::take(&mut self.buffer);
+        body.clear();
+        encode_varint(id, &mut body);
+        encode_type_name(type_name, &mut body);
+        encode_varint(u64::from(T::VERSION), &mut body);
+
+        let mut prev_tick = 0;
+        for (&tick, keyframe) in keyframes {
+            let delta = tick.checked_sub(prev_tick).ok_or(FormatError::InvalidData(
+                "keyframes are not ordered by tick",
+            ))?;
+            encode_varint(delta, &mut body);
+            keyframe.encode(&mut body);
+            prev_tick = tick;
+        }
+
+        let result = self.write_record(RECORD_CURVE, &body);
+        self.buffer = body;
+        result
+    }
+
+    /// Writes the lifetime of the entity with the given id. Readers use the last lifetime written for an id
+    pub fn write_lifetime(&mut self, id: u64, lifetime: &StateLifetime) -> Result<(), FormatError> {
+        let mut body = vec![];
+        encode_varint(id, &mut body);
+        encode_varint(lifetime.spawned, &mut body);
+        // Zero marks an entity that hasn't been despawned
+        encode_varint(
+            lifetime
+                .despawned
+                .map_or(0, |despawned| despawned.saturating_add(1)),
+            &mut body,
+        );
+        self.write_record(RECORD_LIFETIME, &body)
+    }
+
+    /// Writes that the keyframes within the ticks were removed from the curve of type `T` with the given id, such as
+    /// the future discarded by a [`RewindTo`](crate::rewind::RewindTo). Readers remove them from everything written
+    /// for the curve before this record.
+    pub fn write_removal<T: KeyframeCodec>(
+        &mut self,
+        id: u64,
+        ticks: (Bound<GameTick>, Bound<GameTick>),
+    ) -> Result<(), FormatError> {
+        self.write_named_removal(id, &T::type_name(), ticks)
+    }
+
+    /// Writes a removal record for the curve with the given id under the type name, see
+    /// [`TimelineWriter::write_named_keyframes`]
+    pub(crate) fn write_named_removal(
+        &mut self,
+        id: u64,
+        type_name: &str,
+        ticks: (Bound<GameTick>, Bound<GameTick>),
+    ) -> Result<(), FormatError> {
+        if !is_valid_range(&ticks) {
+            return Err(FormatError::InvalidData("invalid removal range"));
+        }
+        let mut body = vec![];
+        encode_varint(id, &mut body);
+        encode_type_name(type_name, &mut body);
+        encode_bound(ticks.0, &mut body);
+        encode_bound(ticks.1, &mut body);
+        self.write_record(RECORD_REMOVAL, &body)
+    }
+
+    fn write_record(&mut self, tag: u8, body: &[u8]) -> Result<(), FormatError> {
+        let mut header = vec![tag];
+        encode_varint(body.len() as u64, &mut header);
+        self.writer.write_all(&header)?;
+        self.writer.write_all(body)?;
+        Ok(())
+    }
+
+    /// Marks the end of the timeline, flushes the writer and returns it
+    pub fn finish(mut self) -> Result<W, FormatError> {
+        self.writer.write_all(&[RECORD_END])?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Reads curves and lifetimes written by a [`TimelineWriter`] one record at a time.
+///
+/// ## Explanation:
+///
+/// - Files of any format version up to [`TIMELINE_FORMAT_VERSION`] can be read. Files of later versions are rejected
+///   with [`FormatError::UnsupportedVersion`], since they may change the layout of existing records.
+/// - Iterating the reader returns every [`TimelineRecord`] in the order they were written. A file that ends without being
+///   [finished](TimelineWriter::finish) ends after its last full record, but a file cut off in the middle of a record,
+///   such as a recording that was interrupted while writing, returns an error after the last full record.
+/// - The reader reads a byte at a time while parsing record headers, wrap it in a [`BufReader`](std::io::BufReader)
+///   when reading from a file.
+///
+/// ```rust,ignore
+/// let reader = TimelineReader::new(BufReader::new(File::open("match.timeline")?))?;
+/// for record in reader {
+///     if let TimelineRecord::Curve(record) = record? {
+///         if record.is::<BodyOrbit>() {
+///             let curve: SteppedCurve<BodyOrbit> = record.decode()?;
+///         }
+///     }
+/// }
+/// ```
+pub struct TimelineReader<R: Read> {
+    reader: R,
+    version: u32,
+    finished: bool,
+}
+
+impl<R: Read> TimelineReader<R> {
+    /// Creates a new reader, reading and checking the file header
+    pub fn new(mut reader: R) -> Result<TimelineReader<R>, FormatError> {
+        let mut magic = [0; 4];
+        match reader.read_exact(&mut magic) {
+            Err(error) if error.kind() == ErrorKind::UnexpectedEof => {
+                return Err(FormatError::InvalidMagic)
+            }
+            result => result?,
+        }
+        if magic != TIMELINE_MAGIC {
+            return Err(FormatError::InvalidMagic);
+        }
+        let version = u32::try_from(read_varint(&mut reader)?)
+            .map_err(|_| FormatError::InvalidData("format version is too large"))?;
+        if version == 0 || version > TIMELINE_FORMAT_VERSION {
+            return Err(FormatError::UnsupportedVersion { version });
+        }
+        Ok(TimelineReader {
+            reader,
+            version,
+            finished: false,
+        })
+    }
+
+    /// Returns the format version the file was written with
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Reads the next record, returning `None` at the end of the timeline
+    pub fn next_record(&mut self) -> Result<Option<TimelineRecord>, FormatError> {
+        while !self.finished {
+            let mut tag = [0];
+            if self.reader.read(&mut tag)? == 0 {
+                self.finished = true;
+                break;
+            }
+            if tag[0] == RECORD_END {
+                self.finished = true;
+                break;
+            }
+            let len = read_varint(&mut self.reader)?;
+            let mut body = vec![];
+            (&mut self.reader).take(len).read_to_end(&mut body)?;
+            if body.len() as u64 != len {
+                return Err(FormatError::InvalidData("truncated record"));
+            }
+            match tag[0] {
+                RECORD_CURVE => {
+                    return CurveRecord::parse(&body)
+                        .map(TimelineRecord::Curve)
+                        .map(Some)
+                }
+                RECORD_LIFETIME if self.version >= 2 => {
+                    return parse_lifetime(&body).map(Some);
+                }
+                RECORD_REMOVAL if self.version >= 2 => {
+                    return parse_removal(&body).map(Some);
+                }
+                _ => {}
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<R: Read> Iterator for TimelineReader<R> {
+    type Item = Result<TimelineRecord, FormatError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = self.next_record();
+        if record.is_err() {
+            self.finished = true;
+        }
+        record.transpose()
+    }
+}
+
+/// A single record read by a [`TimelineReader`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum TimelineRecord {
+    /// The keyframes of a curve
+    Curve(CurveRecord),
+    /// The lifetime of the entity with the id
+    Lifetime {
+        /// The id the lifetime was written with
+        id: u64,
+        /// The lifetime of the entity
+        lifetime: StateLifetime,
+    },
+    /// Keyframes were removed from the curve with the id
+    Removal {
+        /// The id the curve was written with
+        id: u64,
+        /// The [`KeyframeCodec::type_name`] of the curves keyframes
+        type_name: String,
+        /// The range of ticks that keyframes were removed from
+        ticks: (Bound<GameTick>, Bound<GameTick>),
+    },
+}
+
+fn parse_lifetime(mut body: &[u8]) -> Result<TimelineRecord, FormatError> {
+    let id = decode_varint(&mut body)?;
+    let spawned = decode_varint(&mut body)?;
+    let despawned = decode_varint(&mut body)?.checked_sub(1);
+    Ok(TimelineRecord::Lifetime {
+        id,
+        lifetime: StateLifetime { spawned, despawned },
+    })
+}
+
+fn parse_removal(mut body: &[u8]) -> Result<TimelineRecord, FormatError> {
+    let id = decode_varint(&mut body)?;
+    let type_name = decode_type_name(&mut body)?;
+    let ticks = (decode_bound(&mut body)?, decode_bound(&mut body)?);
+    if !is_valid_range(&ticks) {
+        return Err(FormatError::InvalidData("invalid removal range"));
+    }
+    Ok(TimelineRecord::Removal {
+        id,
+        type_name,
+        ticks,
+    })
+}
+
+/// A single curve read by a [`TimelineReader`], holding its still encoded keyframes
+#[derive(Clone, Debug, PartialEq)]
+pub struct CurveRecord {
+    id: u64,
+    type_name: String,
+    keyframe_version: u32,
+    keyframes: Vec<u8>,
+}
+
+impl CurveRecord {
+    fn parse(mut body: &[u8]) -> Result<CurveRecord, FormatError> {
+        let id = decode_varint(&mut body)?;
+        let type_name = decode_type_name(&mut body)?;
+        let keyframe_version = u32::try_from(decode_varint(&mut body)?)
+            .map_err(|_| FormatError::InvalidData("keyframe version is too large"))?;
+        Ok(CurveRecord {
+            id,
+            type_name,
+            keyframe_version,
+            keyframes: body.to_vec(),
+        })
+    }
+
+    /// Returns the id the curve was written with
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns the [`KeyframeCodec::type_name`] of the keyframes the curve was written with
+    pub fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    /// Returns the [`KeyframeCodec::VERSION`] of the keyframes the curve was written with
+    pub fn keyframe_version(&self) -> u32 {
+        self.keyframe_version
+    }
+
+    /// Returns true if the curve was written with keyframes of type `T`
+    pub fn is<T: KeyframeCodec>(&self) -> bool {
+        self.type_name == T::type_name()
+    }
+
+    /// Decodes every keyframe in the record along with its tick
+    pub fn keyframes<T: KeyframeCodec>(&self) -> Result<Vec<(GameTick, T)>, FormatError> {
+        if !self.is::<T>() {
+            return Err(FormatError::TypeMismatch {
+                expected: T::type_name(),
+                found: self.type_name.clone(),
+            });
+        }
+        self.named_keyframes()
+    }
+
+    /// Decodes every keyframe in the record as `T` without checking its type name, for records written with
+    /// [`TimelineWriter::write_named_keyframes`]
+    pub(crate) fn named_keyframes<T: KeyframeCodec>(
+        &self,
+    ) -> Result<Vec<(GameTick, T)>, FormatError> {
+        let mut bytes = self.keyframes.as_slice();
+        let mut keyframes = vec![];
+        let mut tick: GameTick = 0;
+        while !bytes.is_empty() {
+            tick = tick
+                .checked_add(decode_varint(&mut bytes)?)
+                .ok_or(FormatError::InvalidData("tick overflowed"))?;
+            keyframes.push((tick, T::decode(self.keyframe_version, &mut bytes)?));
+        }
+        Ok(keyframes)
+    }
+
+    /// Decodes the keyframes and inserts them into the curve. Nothing is inserted if decoding fails
+    pub fn decode_into<T: KeyframeCodec>(
+        &self,
+        curve: &mut impl CurveTrait<T>,
+    ) -> Result<(), FormatError> {
+        for (tick, keyframe) in self.keyframes()? {
+            curve.insert_keyframe(tick, keyframe);
+        }
+        Ok(())
+    }
+
+    /// Decodes the keyframes into a new curve
+    pub fn decode<T: KeyframeCodec, C: CurveTrait<T>>(&self) -> Result<C, FormatError> {
+        let mut curve = C::new();
+        self.decode_into(&mut curve)?;
+        Ok(curve)
+    }
+}
+
+/// Implements [`KeyframeCodec`] for the unsigned integers as varints
+macro_rules! impl_unsigned_codec {
+    ($($ty:ty),*) => {
+        $(
+            impl KeyframeCodec for $ty {
+                fn type_name() -> String {
+                    stringify!($ty).into()
+                }
+
+                fn encode(&self, buf: &mut Vec<u8>) {
+                    encode_varint(*self as u64, buf);
+                }
+
+                fn decode(_version: u32, bytes: &mut &[u8]) -> Result<Self, FormatError> {
+                    <$ty>::try_from(decode_varint(bytes)?)
+                        .map_err(|_| FormatError::InvalidData("integer out of range"))
+                }
+            }
+        )*
+    };
+}
+
+impl_unsigned_codec!(u8, u16, u32, u64, usize);
+
+/// Implements [`KeyframeCodec`] for the signed integers as zigzag encoded varints, so small negative numbers stay small
+macro_rules! impl_signed_codec {
+    ($($ty:ty),*) => {
+        $(
+            impl KeyframeCodec for $ty {
+                fn type_name() -> String {
+                    stringify!($ty).into()
+                }
+
+                fn encode(&self, buf: &mut Vec<u8>) {
+                    let value = *self as i64;
+                    encode_varint(((value << 1) ^ (value >> 63)) as u64, buf);
+                }
+
+                fn decode(_version: u32, bytes: &mut &[u8]) -> Result<Self, FormatError> {
+                    let value = decode_varint(bytes)?;
+                    let value = (value >> 1) as i64 ^ -((value & 1) as i64);
+                    <$ty>::try_from(value)
+                        .map_err(|_| FormatError::InvalidData("integer out of range"))
+                }
+            }
+        )*
+    };
+}
+
+impl_signed_codec!(i8, i16, i32, i64, isize);
+
+/// Implements [`KeyframeCodec`] for the floats as their little endian bytes
+macro_rules! impl_float_codec {
+    ($($ty:ty),*) => {
+        $(
+            impl KeyframeCodec for $ty {
+                fn type_name() -> String {
+                    stringify!($ty).into()
+                }
+
+                fn encode(&self, buf: &mut Vec<u8>) {
+                    buf.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn decode(_version: u32, bytes: &mut &[u8]) -> Result<Self, FormatError> {
+                    Ok(<$ty>::from_le_bytes(decode_array(bytes)?))
+                }
+            }
+        )*
+    };
+}
+
+impl_float_codec!(f32, f64);
+
+/// Implements [`KeyframeCodec`] for the glam types by encoding each of their components
+macro_rules! impl_glam_codec {
+    ($($ty:ident($scalar:ty; $len:literal)),*) => {
+        $(
+            impl KeyframeCodec for $ty {
+                fn type_name() -> String {
+                    stringify!($ty).into()
+                }
+
+                fn encode(&self, buf: &mut Vec<u8>) {
+                    for component in self.to_array() {
+                        component.encode(buf);
+                    }
+                }
+
+                fn decode(version: u32, bytes: &mut &[u8]) -> Result<Self, FormatError> {
+                    let mut components = [0.0; $len];
+                    for component in components.iter_mut() {
+                        *component = <$scalar>::decode(version, bytes)?;
+                    }
+                    Ok($ty::from_array(components))
+                }
+            }
+        )*
+    };
+}
+
+impl_glam_codec!(
+    Vec2(f32; 2),
+    Vec3(f32; 3),
+    Vec4(f32; 4),
+    Quat(f32; 4),
+    DVec2(f64; 2),
+    DVec3(f64; 3),
+    DVec4(f64; 4)
+);
+
+impl KeyframeCodec for bool {
+    fn type_name() -> String {
+        "bool".into()
+    }
+
+    fn encode(&self, buf: &mut Vec<u8>) {
+        buf.push(*self as u8);
+    }
+
+    fn decode(_version: u32, bytes: &mut &[u8]) -> Result<Self, FormatError> {
+        match decode_array(bytes)? {
+            [0] => Ok(false),
+            [1] => Ok(true),
+            _ => Err(FormatError::InvalidData("invalid bool")),
+        }
+    }
+}
+
+impl KeyframeCodec for String {
+    fn type_name() -> String {
+        "String".into()
+    }
+
+    fn encode(&self, buf: &mut Vec<u8>) {
+        encode_varint(self.len() as u64, buf);
+        buf.extend_from_slice(self.as_bytes());
+    }
+
+    fn decode(_version: u32, bytes: &mut &[u8]) -> Result<Self, FormatError> {
+        let len = decode_varint(bytes)? as usize;
+        if bytes.len() < len {
+            return Err(FormatError::InvalidData("truncated string"));
+        }
+        let (string, rest) = bytes.split_at(len);
+        *bytes = rest;
+        String::from_utf8(string.to_vec()).map_err(|_| FormatError::InvalidData("invalid utf8"))
+    }
+}
+
+/// Writes the [`KeyframeCodec::VERSION`] of `V` in front of every keyframe, so the encoding of [`Ballistic`] and of
+/// its values can change independently
+impl<V: KeyframeCodec> KeyframeCodec for Ballistic<V> {
+    const VERSION: u32 = 0;
+
+    fn type_name() -> String {
+        format!("Ballistic<{}>", V::type_name())
+    }
+
+    fn encode(&self, buf: &mut Vec<u8>) {
+        encode_varint(u64::from(V::VERSION), buf);
+        self.position.encode(buf);
+        self.velocity.encode(buf);
+        self.acceleration.encode(buf);
+    }
+
+    fn decode(_version: u32, bytes: &mut &[u8]) -> Result<Self, FormatError> {
+        let version = u32::try_from(decode_varint(bytes)?)
+            .map_err(|_| FormatError::InvalidData("keyframe version is too large"))?;
+        Ok(Ballistic {
+            position: V::decode(version, bytes)?,
+            velocity: V::decode(version, bytes)?,
+            acceleration: V::decode(version, bytes)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+
+    use super::{
+        decode_varint, encode_varint, KeyframeCodec, TimelineReader, TimelineRecord,
+        TimelineWriter, TIMELINE_MAGIC,
+    };
+    use crate::{
+        error::FormatError,
+        prelude::{Ballistic, CurveTrait, LinearCurve, StateLifetime, SteppedCurve},
+    };
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Orbit {
+        radius: f32,
+        speed: f32,
+    }
+
+    impl crate::prelude::SteppedKeyframe<Orbit> for Orbit {}
+
+    /// The first version of [`Orbit`], which had no speed
+    #[derive(Clone, Debug, PartialEq)]
+    struct OrbitV0 {
+        radius: f32,
+    }
+
+    impl crate::prelude::SteppedKeyframe<OrbitV0> for OrbitV0 {}
+
+    impl KeyframeCodec for OrbitV0 {
+        fn type_name() -> String {
+            "Orbit".into()
+        }
+
+        fn encode(&self, buf: &mut Vec<u8>) {
+            self.radius.encode(buf);
+        }
+
+        fn decode(version: u32, bytes: &mut &[u8]) -> Result<Self, FormatError> {
+            Ok(OrbitV0 {
+                radius: f32::decode(version, bytes)?,
+            })
+        }
+    }
+
+    impl KeyframeCodec for Orbit {
+        const VERSION: u32 = 1;
+
+        fn type_name() -> String {
+            "Orbit".into()
+        }
+
+        fn encode(&self, buf: &mut Vec<u8>) {
+            self.radius.encode(buf);
+            self.speed.encode(buf);
+        }
+
+        fn decode(version: u32, bytes: &mut &[u8]) -> Result<Self, FormatError> {
+            Ok(Orbit {
+                radius: f32::decode(version, bytes)?,
+                speed: match version {
+                    0 => 1.0,
+                    _ => f32::decode(version, bytes)?,
+                },
+            })
+        }
+    }
+
+    #[test]
+    fn test_varints() {
+        let mut buf = vec![];
+        for value in [0, 1, 127, 128, 300, u64::MAX] {
+            encode_varint(value, &mut buf);
+        }
+        assert_eq!(buf.len(), 1 + 1 + 1 + 2 + 2 + 10);
+        let mut bytes = buf.as_slice();
+        for value in [0, 1, 127, 128, 300, u64::MAX] {
+            assert_eq!(decode_varint(&mut bytes).unwrap(), value);
+        }
+        assert!(bytes.is_empty());
+        assert!(decode_varint(&mut [0x80].as_slice()).is_err());
+
+        for value in [0i32, -1, 1, -64, 64, i32::MIN, i32::MAX] {
+            let mut buf = vec![];
+            value.encode(&mut buf);
+            assert_eq!(i32::decode(0, &mut buf.as_slice()).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_timeline_file() {
+        let mut linear = LinearCurve::new();
+        for tick in 0..1000u64 {
+            linear.insert_keyframe(tick + 50, tick as f32);
+        }
+        let mut stepped = SteppedCurve::new();
+        stepped.insert_keyframe(10, OrbitV0 { radius: 2.0 });
+        stepped.insert_keyframe(400, OrbitV0 { radius: 3.0 });
+
+        let mut writer = TimelineWriter::new(vec![]).unwrap();
+        writer.write_curve(1, &linear).unwrap();
+        let lifetime = StateLifetime {
+            spawned: 50,
+            despawned: Some(1050),
+        };
+        writer.write_lifetime(1, &lifetime).unwrap();
+        writer.write_curve(2, &stepped).unwrap();
+        // Streaming more keyframes for a curve that was already written
+        writer
+            .write_keyframes(2, [(&500, &OrbitV0 { radius: 4.0 })])
+            .unwrap();
+        assert!(matches!(
+            writer.write_keyframes(3, [(&5, &1u32), (&4, &1u32)]),
+            Err(FormatError::InvalidData(_))
+        ));
+        let bytes = writer.finish().unwrap();
+        // One byte for each tick and four for each f32
+        assert!(bytes.len() < 1000 * 5 + 100);
+
+        let mut reader = TimelineReader::new(bytes.as_slice()).unwrap();
+        assert_eq!(reader.version(), 2);
+        let Some(TimelineRecord::Curve(record)) = reader.next_record().unwrap() else {
+            panic!("expected a curve record");
+        };
+        assert_eq!(record.id(), 1);
+        assert!(record.is::<f32>());
+        assert!(matches!(
+            record.decode::<f64, LinearCurve<f64>>(),
+            Err(FormatError::TypeMismatch { .. })
+        ));
+        let decoded: LinearCurve<f32> = record.decode().unwrap();
+        assert!(decoded.range(..).eq(linear.range(..)));
+        assert_eq!(
+            reader.next_record().unwrap(),
+            Some(TimelineRecord::Lifetime { id: 1, lifetime })
+        );
+
+        // Files written before a keyframe type changed can still be read with its new version
+        let mut orbits = SteppedCurve::<Orbit>::new();
+        for record in reader.by_ref() {
+            let Ok(TimelineRecord::Curve(record)) = record else {
+                panic!("expected a curve record");
+            };
+            assert_eq!((record.id(), record.keyframe_version()), (2, 0));
+            record.decode_into(&mut orbits).unwrap();
+        }
+        assert_eq!(
+            orbits.get_state(450),
+            Some(Orbit {
+                radius: 3.0,
+                speed: 1.0
+            })
+        );
+        assert_eq!(orbits.range(..).count(), 3);
+        assert!(reader.next().is_none());
+
+        // The record of the invalid write is left out, but a file that is cut off mid record is an error
+        let cut = &bytes[..bytes.len() - 3];
+        let results = TimelineReader::new(cut).unwrap().collect::<Vec<_>>();
+        assert_eq!(results.len(), 4);
+        assert!(results[3].is_err());
+
+        // Version 1 files had no lifetime records but are otherwise read the same
+        let mut version_1 = bytes.clone();
+        version_1[TIMELINE_MAGIC.len()] = 1;
+        let reader = TimelineReader::new(version_1.as_slice()).unwrap();
+        assert_eq!(reader.version(), 1);
+        let records = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(records.len(), 3);
+        assert!(records
+            .iter()
+            .all(|record| matches!(record, TimelineRecord::Curve(_))));
+
+        assert!(matches!(
+            TimelineReader::new(b"nope".as_slice()),
+            Err(FormatError::InvalidMagic)
+        ));
+        let mut future = TIMELINE_MAGIC.to_vec();
+        encode_varint(99, &mut future);
+        assert!(matches!(
+            TimelineReader::new(future.as_slice()),
+            Err(FormatError::UnsupportedVersion { version: 99 })
+        ));
+    }
+
+    #[test]
+    fn test_ballistic_versions() {
+        let old = Ballistic {
+            position: OrbitV0 { radius: 1.0 },
+            velocity: OrbitV0 { radius: 2.0 },
+            acceleration: OrbitV0 { radius: 3.0 },
+        };
+        let mut buf = vec![];
+        old.encode(&mut buf);
+        // The version of the values is kept even though the version of the ballistic encoding didn't change
+        assert_eq!(Ballistic::<Orbit>::VERSION, Ballistic::<OrbitV0>::VERSION);
+        let decoded =
+            Ballistic::<Orbit>::decode(Ballistic::<OrbitV0>::VERSION, &mut buf.as_slice()).unwrap();
+        assert_eq!(
+            decoded.acceleration,
+            Orbit {
+                radius: 3.0,
+                speed: 1.0
+            }
+        );
+    }
+
+    #[test]
+    fn test_removal_records() {
+        let mut writer = TimelineWriter::new(vec![]).unwrap();
+        writer
+            .write_keyframes(1, [(&0, &1u32), (&10, &2u32)])
+            .unwrap();
+        writer
+            .write_removal::<u32>(1, (Bound::Excluded(5), Bound::Unbounded))
+            .unwrap();
+        assert!(matches!(
+            writer.write_removal::<u32>(1, (Bound::Included(10), Bound::Excluded(5))),
+            Err(FormatError::InvalidData(_))
+        ));
+        let bytes = writer.finish().unwrap();
+
+        let records = TimelineReader::new(bytes.as_slice())
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            records[1],
+            TimelineRecord::Removal {
+                id: 1,
+                type_name: "u32".into(),
+                ticks: (Bound::Excluded(5), Bound::Unbounded),
+            }
+        );
+    }
+}
@@ -0,0 +1,212 @@
+use bevy::{
+    math::{DQuat, DVec2, DVec3, DVec4, Quat, Vec2, Vec3, Vec3A, Vec4},
+    prelude::{Entity, Transform},
+};
+
+#[cfg(feature = "bevy_color")]
+use bevy::color::{Color, LinearRgba, Mix, Oklaba, Srgba};
+
+use crate::keyframe_trait::{LinearKeyframe, PulseKeyframe, SteppedKeyframe};
+
+/// Implements [`LinearKeyframe`] for types that can be scaled by their scalar type, so they can be used directly in a
+/// [`LinearCurve`](crate::curves::LinearCurve) or as fields of a type deriving [`LinearKeyframe`].
+macro_rules! impl_scalar_linear_keyframe {
+    ($scalar:ty => $($ty:ty),*) => {
+        $(
+            impl LinearKeyframe<$ty> for $ty {
+                fn lerp(&self, next_frame_state: &$ty, ratio: f64) -> $ty {
+                    *self + (*next_frame_state - *self) * ratio as $scalar
+                }
+            }
+        )*
+    };
+}
+
+impl_scalar_linear_keyframe!(f32 => f32, Vec2, Vec3, Vec3A, Vec4);
+impl_scalar_linear_keyframe!(f64 => f64, DVec2, DVec3, DVec4);
+
+/// Implements [`LinearKeyframe`] for the integers by interpolating as an `f64` and rounding to the nearest integer.
+macro_rules! impl_integer_linear_keyframe {
+    ($($ty:ty),*) => {
+        $(
+            impl LinearKeyframe<$ty> for $ty {
+                fn lerp(&self, next_frame_state: &$ty, ratio: f64) -> $ty {
+                    let start = *self as f64;
+                    (start + (*next_frame_state as f64 - start) * ratio).round() as $ty
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_linear_keyframe!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// Rotations are spherically interpolated so they turn at a constant speed along the shortest path.
+impl LinearKeyframe<Quat> for Quat {
+    fn lerp(&self, next_frame_state: &Quat, ratio: f64) -> Quat {
+        self.slerp(*next_frame_state, ratio as f32)
+    }
+}
+
+/// Rotations are spherically interpolated so they turn at a constant speed along the shortest path.
+impl LinearKeyframe<DQuat> for DQuat {
+    fn lerp(&self, next_frame_state: &DQuat, ratio: f64) -> DQuat {
+        self.slerp(*next_frame_state, ratio)
+    }
+}
+
+/// The translation and scale are linearly interpolated while the rotation is spherically interpolated.
+impl LinearKeyframe<Transform> for Transform {
+    fn lerp(&self, next_frame_state: &Transform, ratio: f64) -> Transform {
+        Transform {
+            translation: self
+                .translation
+                .lerp(next_frame_state.translation, ratio as f32),
+            rotation: self.rotation.slerp(next_frame_state.rotation, ratio as f32),
+            scale: self.scale.lerp(next_frame_state.scale, ratio as f32),
+        }
+    }
+}
+
+/// Colors are mixed in the [`Oklaba`] color space, which keeps the perceived brightness of the blend even, and the
+/// state is returned as [`Color::Oklaba`]. Use one of the concrete color types to interpolate in another color space.
+#[cfg(feature = "bevy_color")]
+impl LinearKeyframe<Color> for Color {
+    fn lerp(&self, next_frame_state: &Color, ratio: f64) -> Color {
+        Oklaba::from(*self)
+            .mix(&Oklaba::from(*next_frame_state), ratio as f32)
+            .into()
+    }
+}
+
+/// Implements [`LinearKeyframe`] for the concrete color types by mixing in their own color space.
+#[cfg(feature = "bevy_color")]
+macro_rules! impl_color_linear_keyframe {
+    ($($ty:ty),*) => {
+        $(
+            impl LinearKeyframe<$ty> for $ty {
+                fn lerp(&self, next_frame_state: &$ty, ratio: f64) -> $ty {
+                    self.mix(next_frame_state, ratio as f32)
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "bevy_color")]
+impl_color_linear_keyframe!(LinearRgba, Oklaba, Srgba);
+
+/// Implements [`SteppedKeyframe`] and [`PulseKeyframe`] for types that don't have a meaningful interpolation.
+macro_rules! impl_discrete_keyframe {
+    ($($ty:ty),*) => {
+        $(
+            impl SteppedKeyframe<$ty> for $ty {}
+
+            impl PulseKeyframe<$ty> for $ty {}
+        )*
+    };
+}
+
+impl_discrete_keyframe!(bool, u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, String, Entity);
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use bevy::{
+        math::{Quat, Vec2, Vec3},
+        prelude::Transform,
+    };
+
+    use crate::prelude::{CurveTrait, LinearCurve, LinearKeyframe, SteppedCurve};
+
+    #[test]
+    fn test_glam_keyframes() {
+        assert_eq!(LinearKeyframe::lerp(&1.0_f32, &3.0, 0.5), 2.0);
+        assert_eq!(
+            LinearKeyframe::lerp(&Vec2::ZERO, &Vec2::new(2.0, 4.0), 0.25),
+            Vec2::new(0.5, 1.0)
+        );
+        assert_eq!(LinearKeyframe::lerp(&10_u32, &20, 0.26), 13);
+
+        let mut curve = LinearCurve::new();
+        curve.insert_keyframe(0, 0.0_f64);
+        curve.insert_keyframe(10, 5.0_f64);
+        assert_eq!(curve.get_state(4), Some(2.0));
+
+        let mut stepped = SteppedCurve::new();
+        stepped.insert_keyframe(0, String::from("idle"));
+        stepped.insert_keyframe(10, String::from("moving"));
+        assert_eq!(stepped.get_state(5), Some(String::from("idle")));
+    }
+
+    #[test]
+    fn test_transform_keyframes() {
+        let start = Transform::IDENTITY;
+        let end = Transform::from_xyz(10.0, 0.0, 0.0)
+            .with_rotation(Quat::from_rotation_z(FRAC_PI_2))
+            .with_scale(Vec3::splat(3.0));
+        let half = start.lerp(&end, 0.5);
+        assert_eq!(half.translation, Vec3::new(5.0, 0.0, 0.0));
+        assert_eq!(half.scale, Vec3::splat(2.0));
+        // A component wise lerp would shorten the quaternion, slerp keeps it normalized
+        assert!(half.rotation.is_normalized());
+        assert!(half
+            .rotation
+            .abs_diff_eq(Quat::from_rotation_z(FRAC_PI_2 / 2.0), 1e-6));
+    }
+
+    #[cfg(feature = "bevy_color")]
+    #[test]
+    fn test_color_keyframes() {
+        use bevy::color::{Color, LinearRgba, Oklaba};
+
+        let black = Color::BLACK;
+        let white = Color::WHITE;
+        assert_eq!(black.lerp(&white, 0.0), Color::Oklaba(Oklaba::from(black)));
+        let grey = LinearRgba::from(black.lerp(&white, 0.5));
+        assert!(grey.red > 0.0 && grey.red < 1.0);
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_derive_keyframes() {
+        use crate::prelude::{PulseKeyframe, SteppedKeyframe};
+
+        #[derive(Clone, Debug, PartialEq, LinearKeyframe)]
+        struct Body {
+            position: Vec2,
+            radius: f32,
+            #[keyframe(step)]
+            orbits: u32,
+            #[keyframe(default)]
+            hovered: bool,
+        }
+
+        #[derive(Clone, Debug, PartialEq, LinearKeyframe, SteppedKeyframe, PulseKeyframe)]
+        struct Angle(f64);
+
+        let start = Body {
+            position: Vec2::ZERO,
+            radius: 1.0,
+            orbits: 1,
+            hovered: true,
+        };
+        let end = Body {
+            position: Vec2::new(10.0, 0.0),
+            radius: 3.0,
+            orbits: 2,
+            hovered: true,
+        };
+        assert_eq!(
+            start.lerp(&end, 0.5),
+            Body {
+                position: Vec2::new(5.0, 0.0),
+                radius: 2.0,
+                orbits: 1,
+                hovered: false,
+            }
+        );
+        assert_eq!(Angle(0.0).lerp(&Angle(1.0), 0.25), Angle(0.25));
+    }
+}
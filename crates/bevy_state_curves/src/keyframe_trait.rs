@@ -1,3 +1,16 @@
+use std::ops::{Add, Mul};
+
+use bevy::prelude::Component;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(all(feature = "type_path", not(feature = "reflect")))]
+use bevy::reflect::TypePath;
+
+#[cfg(feature = "reflect")]
+use bevy::{ecs::reflect::ReflectComponent, reflect::Reflect};
+
 /// A trait that must be implemented to allow a type to be used in a [`SteppedCurve`](crate::curves::SteppedCurve)
 pub trait SteppedKeyframe<T>: Clone {}
 
@@ -10,3 +23,75 @@ pub trait LinearKeyframe<T>: Clone {
     /// The implementation decides which fields are interpolated and returns the result for use in systems
     fn lerp(&self, next_frame_state: &T, ratio: f64) -> T;
 }
+
+/// A metric measuring how far apart two keyframes are, used by [`LinearCurve::simplify`](crate::curves::LinearCurve::simplify)
+/// to decide if a keyframe can be removed.
+///
+/// Implemented for any `Fn(&T, &T) -> f64`, so a closure such as `|a: &f32, b: &f32| (a - b).abs() as f64` works.
+pub trait KeyframeDistance<T> {
+    /// Returns the distance between the two keyframes. Must be zero for equal keyframes and never negative
+    fn distance(&self, a: &T, b: &T) -> f64;
+}
+
+impl<T, F: Fn(&T, &T) -> f64> KeyframeDistance<T> for F {
+    fn distance(&self, a: &T, b: &T) -> f64 {
+        self(a, b)
+    }
+}
+
+/// A trait that must be implemented to allow a type to be used in a [`CubicCurve`](crate::curves::CubicCurve)
+pub trait CubicKeyframe<T>: Clone {
+    /// Returns `self + other * factor`.
+    ///
+    /// The curve builds the cubic interpolation out of this operation so every interpolated field should be
+    /// included. Fields that shouldn't be interpolated can simply be copied from `self`.
+    fn add_scaled(&self, other: &T, factor: f64) -> T;
+
+    /// The tangent, as the rate of change per [`GameTick`](crate::GameTick), of the state at this keyframe.
+    ///
+    /// Return `None` to have the curve derive a Catmull-Rom tangent from the neighbouring keyframes instead.
+    fn tangent(&self) -> Option<T> {
+        None
+    }
+}
+
+/// A trait that must be implemented to allow a type to be used in a [`PhysicsCurve`](crate::curves::PhysicsCurve)
+pub trait PhysicsKeyframe<T>: Clone {
+    /// Extrapolates the state `elapsed` [`GameTick`](crate::GameTick)s after this keyframe using the derivatives stored on the keyframe.
+    ///
+    /// For a keyframe storing velocity and acceleration this is `position + velocity * elapsed + 0.5 * acceleration * elapsed²`.
+    /// See [`Ballistic`] for a ready made implementation.
+    fn extrapolate(&self, elapsed: f64) -> T;
+}
+
+/// A [`PhysicsKeyframe`] storing a position along with its velocity and acceleration per [`GameTick`](crate::GameTick).
+///
+/// Works with any value that can be added together and scaled by an `f32`, such as `f32`, `Vec2`, and `Vec3`.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(all(feature = "type_path", not(feature = "reflect")), derive(TypePath))]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct Ballistic<V> {
+    /// The position at the keyframe
+    pub position: V,
+    /// The change in position per tick
+    pub velocity: V,
+    /// The change in velocity per tick
+    pub acceleration: V,
+}
+
+impl<V> PhysicsKeyframe<Ballistic<V>> for Ballistic<V>
+where
+    V: Copy + Add<Output = V> + Mul<f32, Output = V>,
+{
+    fn extrapolate(&self, elapsed: f64) -> Ballistic<V> {
+        let elapsed = elapsed as f32;
+        Ballistic {
+            position: self.position
+                + self.velocity * elapsed
+                + self.acceleration * (0.5 * elapsed * elapsed),
+            velocity: self.velocity + self.acceleration * elapsed,
+            acceleration: self.acceleration,
+        }
+    }
+}
@@ -57,15 +57,27 @@
 //!     }
 //! ```
 //!
+//! 4. Optionally, add the `CurvesPlugin` and register your curves to have their state written onto the entity automatically.
+//!
+//! ```rust ignore
+//!     app.add_plugins(CurvesPlugin);
+//!     app.register_curve::<LinearCurve<ObjectRadius>>();
+//!
+//!     // Every registered curve is sampled at this tick and its state inserted as a component on the entity
+//!     fn scrub(mut viewed_tick: ResMut<ViewedTick>){
+//!         viewed_tick.0 = 5;
+//!     }
+//! ```
+//!
 //! ### GameTick
 //!
-//! This crate relies on an internal `GameTick` type alias of a u64 to create a sense of time. Higher ticks are considered later/older chronologically than lower numbered ticks. When using this crate you will have to decide what the ticks will look like in your crate and implement those systems yourself. This crate does nothing with `GameTick` other than provide it and use it as an identifier of state keyframes.
+//! This crate relies on an internal `GameTick` type alias of a u64 to create a sense of time. Higher ticks are considered later/older chronologically than lower numbered ticks. When using this crate you will have to decide what the ticks will look like in your crate and implement those systems yourself. The curves themselves do nothing with `GameTick` other than use it as an identifier of state keyframes.
 //!
 //! See the [solar_system.rs](https://github.com/NoahShomette/bevy_state_curves/blob/main/crates/bevy_state_curves/examples/solar_system.rs) example for an example of using `GameTick` in a game concept.
 //!
 //! ## Curves
 //!
-//! This crate supports three types of curves. See the docs.rs documentation for each one for details on how they work. Each of these is a Bevy Component.
+//! This crate supports five types of curves. See the docs.rs documentation for each one for details on how they work. Each of these is a Bevy Component.
 //!
 //! - `LinearCurve<T: LinearKeyFrame>`
 //!   - Linearly interpolates state between each keyframe on either side of it.
@@ -73,15 +85,102 @@
 //!   - Flat state between keyframes, state is always the same as the last keyframe.
 //! - `PulseCurve<T: PulseKeyFrame>`
 //!   - Keyframes are only valid on the tick that they exist on.
+//! - `CubicCurve<T: CubicKeyframe>`
+//!   - Smoothly interpolates state between keyframes using a cubic Hermite spline. Tangents are taken from the keyframes or derived Catmull-Rom style from their neighbours.
+//! - `PhysicsCurve<T: PhysicsKeyframe>`
+//!   - Keyframes store their own derivatives, such as velocity and acceleration, and state is extrapolated from the last keyframe. `Ballistic<V>` is a ready made keyframe for this curve.
+//!
+//! ## Features
+//!
+//! See the docs of each item for details.
+//!
+//! - [`TimelinePlugin`](prelude::TimelinePlugin) adds a [`Timeline`](prelude::Timeline) that advances a simulation head and a viewed tick every `FixedUpdate`, with play, pause, scrubbing and playback rate.
+//! - [`CurveTrait`](prelude::CurveTrait) samples state in between ticks with `get_state_at` for smooth rendering.
+//! - Curves are generic over their [`Tick`](prelude::Tick) type, such as a wrapping [`WrappingTick`](prelude::WrappingTick) network tick or [`SecondsTick`](prelude::SecondsTick), with `GameTick` as the default.
+//! - Every curve is a bevy `Curve<Option<T>>`, so it works with bevy's curve adaptors.
+//! - Built in keyframes for the numeric primitives, glam vectors, `Quat`, `Transform`, `Color`, `bool`, `String` and `Entity`.
+//! - [`RewindTo`](prelude::RewindTo) rewinds every registered curve to a past tick and branches the simulation from it.
+//! - [`StateLifetime`](prelude::StateLifetime) limits state to the ticks an entity exists on and marks it [`Dormant`](prelude::Dormant) outside of them.
+//! - [`CurveMut`](prelude::CurveMut) sends [`KeyframeInserted`](prelude::KeyframeInserted) and [`KeyframesRemoved`](prelude::KeyframesRemoved) events for every change to a curve.
+//! - [`CurveChangeLog`](prelude::CurveChangeLog) records changes into [`CurvePatch`](prelude::CurvePatch) updates for replicating curves over the network.
+//! - `LinearCurve::simplify` removes keyframes that interpolation reproduces within a tolerance.
+//! - `SteppedCurve::insert_if_changed` and `SteppedCurve::dedup` skip keyframes equal to the state before them.
+//! - [`CurveRetention`](prelude::CurveRetention) prunes old keyframes from curves registered with [`RetainCurve`](prelude::RetainCurve).
+//! - [`CurveStorage`](prelude::CurveStorage) backends, including [`VecStorage`](prelude::VecStorage) and [`BoundedStorage`](prelude::BoundedStorage), for storing keyframes.
+//! - [`CurveSamples`](prelude::CurveSamples) samples every curve of a type at once, and [`CurveCursor`](prelude::CurveCursor) speeds up sampling at steadily moving ticks.
+//! - Fallible `try_*` methods that return a [`CurveError`](prelude::CurveError), and `freeze_history` to reject edits to past keyframes.
+//! - `ReflectCurve` type data for listing, sampling and editing curves without knowing their keyframe type, under the "reflect" feature.
+//! - Reflected curves round-trip through bevy's `DynamicScene`, remapping the entities their keyframes reference.
+//! - [`TimelineWriter`](prelude::TimelineWriter) and [`TimelineReader`](prelude::TimelineReader) for a compact, versioned binary format.
+//! - [`ReplayPlugin`](prelude::ReplayPlugin) records matches with a [`ReplayRecorder`](prelude::ReplayRecorder) and plays them back with a [`ReplayPlayer`](prelude::ReplayPlayer).
+//! - `CurveAsset`s loaded from `.curve.ron` files under the "asset" feature.
 
+#[cfg(feature = "asset")]
+mod asset;
 mod curves;
+mod error;
+mod events;
+mod format;
+mod keyframe_impls;
 mod keyframe_trait;
+mod lifetime;
+mod math_curve;
+mod plugin;
+#[cfg(feature = "reflect")]
+mod reflect;
+mod replay;
+mod replication;
+mod retention;
+mod rewind;
+mod sampling;
+mod scene;
+mod storage;
+mod tick;
+mod timeline;
 
 pub mod prelude {
-    pub use super::curves::{CurveTrait, LinearCurve, PulseCurve, SteppedCurve};
-    pub use super::keyframe_trait::{LinearKeyframe, PulseKeyframe, SteppedKeyframe};
+    #[cfg(feature = "asset")]
+    pub use super::asset::{CurveAsset, CurveAssetHandle, CurveAssetLoader, RegisterCurveAsset};
+    pub use super::curves::{
+        CubicCurve, CurveTrait, LinearCurve, PhysicsCurve, PulseCurve, SteppedCurve,
+    };
+    #[cfg(feature = "asset")]
+    pub use super::error::CurveAssetError;
+    #[cfg(feature = "reflect")]
+    pub use super::error::ReflectCurveError;
+    pub use super::error::{CurveError, FormatError};
+    pub use super::events::{CurveMut, KeyframeInserted, KeyframesRemoved};
+    pub use super::format::{
+        decode_varint, encode_varint, CurveRecord, KeyframeCodec, TimelineReader, TimelineRecord,
+        TimelineWriter, TIMELINE_FORMAT_VERSION, TIMELINE_MAGIC,
+    };
+    pub use super::keyframe_trait::{
+        Ballistic, CubicKeyframe, KeyframeDistance, LinearKeyframe, PhysicsKeyframe, PulseKeyframe,
+        SteppedKeyframe,
+    };
+    pub use super::lifetime::{Dormant, StateLifetime};
+    pub use super::plugin::{
+        ComponentCurve, CurveSystems, CurvesPlugin, RegisterCurve, ViewedTick,
+    };
+    #[cfg(feature = "reflect")]
+    pub use super::reflect::ReflectCurve;
+    pub use super::replay::{
+        is_replaying, ReplayCurve, ReplayPlayer, ReplayPlugin, ReplayRecorder,
+    };
+    pub use super::replication::{CurveChangeLog, CurvePatch, PatchOp, ReplicateCurve};
+    pub use super::retention::{CurveRetention, RetainCurve};
+    pub use super::rewind::{CurveRegistry, RewindCommands, RewindTo};
+    pub use super::sampling::{BatchSampleCurve, CurveCursor, CurveSamples};
+    pub use super::storage::{BoundedStorage, CurveStorage, VecStorage};
+    pub use super::tick::{SecondsTick, Tick, WrappingTick};
+    pub use super::timeline::{PlaybackDirection, Timeline, TimelinePlugin, ViewedTickChanged};
     pub use super::GameTick;
+    #[cfg(feature = "derive")]
+    pub use bevy_state_curves_derive::{LinearKeyframe, PulseKeyframe, SteppedKeyframe};
 }
 
+// Lets the derive macros refer to `::bevy_state_curves` from inside this crate
+extern crate self as bevy_state_curves;
+
 /// An alias for a u64 representing the type used to drive the state curves.
 pub type GameTick = u64;
@@ -0,0 +1,175 @@
+use bevy::prelude::{Commands, Component, DetectChanges, Entity, Has, Query, Ref, Res, World};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(all(feature = "type_path", not(feature = "reflect")))]
+use bevy::reflect::TypePath;
+
+#[cfg(feature = "reflect")]
+use bevy::{ecs::reflect::ReflectComponent, reflect::Reflect};
+
+use crate::{curves::CurveTrait, error::CurveError, plugin::ViewedTick, GameTick};
+
+/// Marks when an entity exists on the timeline.
+///
+/// ## Explanation:
+///
+/// - The entity exists from the `spawned` tick onwards, including the `spawned` tick itself.
+/// - If the entity has been `despawned` it no longer exists on that tick or any tick after it.
+/// - Curves registered with the [`CurvesPlugin`](crate::plugin::CurvesPlugin) have no state while the entity doesn't
+///   exist at the [`ViewedTick`] and the entity is marked [`Dormant`].
+/// - [`RewindTo`](crate::rewind::RewindTo) despawns entities spawned after the rewound tick and clears despawns after
+///   it, since both belong to the discarded future.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(all(feature = "type_path", not(feature = "reflect")), derive(TypePath))]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct StateLifetime {
+    /// The first tick that the entity exists on
+    pub spawned: GameTick,
+    /// The first tick that the entity no longer exists on, if it has been despawned
+    pub despawned: Option<GameTick>,
+}
+
+impl StateLifetime {
+    /// Creates a new [`StateLifetime`] for an entity spawned on the given tick
+    pub fn new(spawned: GameTick) -> StateLifetime {
+        StateLifetime {
+            spawned,
+            despawned: None,
+        }
+    }
+
+    /// Marks the entity as despawned on the given tick
+    pub fn despawn_at(&mut self, tick: GameTick) {
+        self.despawned = Some(tick);
+    }
+
+    /// Returns true if the entity exists on the given tick
+    pub fn exists_at(&self, tick: GameTick) -> bool {
+        tick >= self.spawned && self.despawned.is_none_or(|despawned| tick < despawned)
+    }
+
+    /// Samples the curve at the given tick, returning `None` if the entity doesn't exist on that tick
+    pub fn get_state<T>(&self, curve: &impl CurveTrait<T>, tick: GameTick) -> Option<T> {
+        if !self.exists_at(tick) {
+            return None;
+        }
+        curve.get_state(tick)
+    }
+
+    /// Fallible version of [`StateLifetime::get_state`] that says why there is no state
+    pub fn try_get_state<T>(
+        &self,
+        curve: &impl CurveTrait<T>,
+        tick: GameTick,
+    ) -> Result<T, CurveError> {
+        if !self.exists_at(tick) {
+            return Err(CurveError::TickOutsideLifetime { tick });
+        }
+        curve.try_get_state(tick)
+    }
+}
+
+/// Marker added by the [`CurvesPlugin`](crate::plugin::CurvesPlugin) to entities whose [`StateLifetime`] doesn't
+/// include the [`ViewedTick`]. Filter with `Without<Dormant>` to skip entities that don't exist at the viewed tick.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Dormant;
+
+/// Adds or removes [`Dormant`] from every entity with a [`StateLifetime`] to match the [`ViewedTick`]
+pub(crate) fn update_dormant(
+    viewed_tick: Res<ViewedTick>,
+    lifetimes: Query<(Entity, Ref<StateLifetime>, Has<Dormant>)>,
+    mut commands: Commands,
+) {
+    let tick_changed = viewed_tick.is_changed();
+    for (entity, lifetime, dormant) in lifetimes.iter() {
+        if !tick_changed && !lifetime.is_changed() {
+            continue;
+        }
+        match (lifetime.exists_at(viewed_tick.0), dormant) {
+            (true, true) => {
+                commands.entity(entity).remove::<Dormant>();
+            }
+            (false, false) => {
+                commands.entity(entity).insert(Dormant);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Rewinds every [`StateLifetime`] to the given tick. See [`StateLifetime`]
+pub(crate) fn rewind_lifetimes(world: &mut World, tick: GameTick) {
+    let mut lifetimes = world.query::<(Entity, &mut StateLifetime)>();
+    let mut unborn = Vec::new();
+    for (entity, mut lifetime) in lifetimes.iter_mut(world) {
+        if lifetime.spawned > tick {
+            unborn.push(entity);
+        } else if lifetime.despawned.is_some_and(|despawned| despawned > tick) {
+            lifetime.despawned = None;
+        }
+    }
+    for entity in unborn {
+        world.despawn(entity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::world::Command,
+        prelude::{App, Component},
+    };
+
+    use super::{Dormant, StateLifetime};
+    use crate::prelude::{
+        CurveTrait, CurvesPlugin, RegisterCurve, RewindTo, SteppedCurve, SteppedKeyframe,
+        ViewedTick,
+    };
+
+    #[derive(Component, Clone, Debug, PartialEq)]
+    struct Health(u32);
+
+    impl SteppedKeyframe<Health> for Health {}
+
+    #[test]
+    fn test_state_lifetime() {
+        let mut app = App::new();
+        app.add_plugins(CurvesPlugin);
+        app.register_curve::<SteppedCurve<Health>>();
+
+        let mut curve = SteppedCurve::new();
+        curve.insert_keyframe(0, Health(10));
+        let mut lifetime = StateLifetime::new(5);
+        lifetime.despawn_at(20);
+        let entity = app.world_mut().spawn((curve, lifetime)).id();
+        let future = app
+            .world_mut()
+            .spawn((SteppedCurve::<Health>::new(), StateLifetime::new(30)))
+            .id();
+
+        app.update();
+        assert!(app.world().get::<Health>(entity).is_none());
+        assert!(app.world().get::<Dormant>(entity).is_some());
+
+        app.world_mut().resource_mut::<ViewedTick>().0 = 10;
+        app.update();
+        assert_eq!(app.world().get::<Health>(entity), Some(&Health(10)));
+        assert!(app.world().get::<Dormant>(entity).is_none());
+
+        app.world_mut().resource_mut::<ViewedTick>().0 = 20;
+        app.update();
+        assert!(app.world().get::<Health>(entity).is_none());
+        assert!(app.world().get::<Dormant>(entity).is_some());
+
+        RewindTo(15).apply(app.world_mut());
+        assert!(app.world().get_entity(future).is_err());
+        assert_eq!(
+            app.world().get::<StateLifetime>(entity),
+            Some(&StateLifetime::new(5))
+        );
+        assert_eq!(app.world().get::<Health>(entity), Some(&Health(10)));
+    }
+}
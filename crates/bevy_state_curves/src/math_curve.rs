@@ -0,0 +1,175 @@
+use bevy::math::curve::{Curve as BevyCurve, Interval};
+
+use crate::{
+    curves::{CubicCurve, CurveTrait, LinearCurve, PhysicsCurve, PulseCurve, SteppedCurve},
+    keyframe_trait::{
+        CubicKeyframe, LinearKeyframe, PhysicsKeyframe, PulseKeyframe, SteppedKeyframe,
+    },
+    storage::CurveStorage,
+    tick::Tick,
+};
+
+/// Returns the interval between the first and last keyframe of the curve.
+///
+/// ## Explanation:
+///
+/// - A curve with a single keyframe holds that keyframe forever so its domain is unbounded after it.
+/// - If `unbounded_end` is set the domain always runs from the first keyframe onwards.
+/// - A curve without any keyframes has no state anywhere. Bevy's [`Interval`] can't be empty so the domain is
+///   [`Interval::EVERYWHERE`] and sampling it returns `None`, see [`sample_held`].
+fn keyframe_domain<T, K: Tick>(curve: &impl CurveTrait<T, K>, unbounded_end: bool) -> Interval {
+    let (Some((first, _)), Some((last, _))) = (curve.first_keyframe(), curve.last_keyframe())
+    else {
+        return Interval::EVERYWHERE;
+    };
+    let start = first.as_f64() as f32;
+    let end = last.as_f64() as f32;
+    if unbounded_end || end <= start {
+        return Interval::new(start, f32::INFINITY).unwrap_or(Interval::EVERYWHERE);
+    }
+    Interval::new(start, end).unwrap_or(Interval::EVERYWHERE)
+}
+
+/// Samples the curve at `t`, clamping times before the first keyframe to the first keyframe.
+///
+/// Returns `None` if the curve has no keyframes.
+fn sample_held<T: Clone, K: Tick>(curve: &impl CurveTrait<T, K>, t: f32) -> Option<T> {
+    curve
+        .get_state_at(t as f64)
+        .or_else(|| curve.first_keyframe().map(|(_, keyframe)| keyframe.clone()))
+}
+
+/// A [`LinearCurve`] is a bevy [`Curve`](BevyCurve) of `Option<T>` over the ticks between its first and last keyframe.
+///
+/// Every sample is `Some` unless the curve has no keyframes.
+impl<T: LinearKeyframe<T>, K: Tick, S: CurveStorage<K, T>> BevyCurve<Option<T>>
+    for LinearCurve<T, K, S>
+{
+    fn domain(&self) -> Interval {
+        keyframe_domain(self, false)
+    }
+
+    fn sample_unchecked(&self, t: f32) -> Option<T> {
+        sample_held(self, t)
+    }
+}
+
+/// A [`SteppedCurve`] is a bevy [`Curve`](BevyCurve) of `Option<T>` over the ticks between its first and last keyframe.
+///
+/// Every sample is `Some` unless the curve has no keyframes.
+impl<T: SteppedKeyframe<T>, K: Tick, S: CurveStorage<K, T>> BevyCurve<Option<T>>
+    for SteppedCurve<T, K, S>
+{
+    fn domain(&self) -> Interval {
+        keyframe_domain(self, false)
+    }
+
+    fn sample_unchecked(&self, t: f32) -> Option<T> {
+        sample_held(self, t)
+    }
+}
+
+/// A [`CubicCurve`] is a bevy [`Curve`](BevyCurve) of `Option<T>` over the ticks between its first and last keyframe.
+///
+/// Every sample is `Some` unless the curve has no keyframes.
+impl<T: CubicKeyframe<T>, K: Tick, S: CurveStorage<K, T>> BevyCurve<Option<T>>
+    for CubicCurve<T, K, S>
+{
+    fn domain(&self) -> Interval {
+        keyframe_domain(self, false)
+    }
+
+    fn sample_unchecked(&self, t: f32) -> Option<T> {
+        sample_held(self, t)
+    }
+}
+
+/// A [`PhysicsCurve`] is a bevy [`Curve`](BevyCurve) of `Option<T>` from its first keyframe onwards since it
+/// extrapolates forever.
+///
+/// Every sample is `Some` unless the curve has no keyframes.
+impl<T: PhysicsKeyframe<T>, K: Tick, S: CurveStorage<K, T>> BevyCurve<Option<T>>
+    for PhysicsCurve<T, K, S>
+{
+    fn domain(&self) -> Interval {
+        keyframe_domain(self, true)
+    }
+
+    fn sample_unchecked(&self, t: f32) -> Option<T> {
+        sample_held(self, t)
+    }
+}
+
+/// A [`PulseCurve`] only has state on the exact tick of a keyframe so it is a bevy [`Curve`](BevyCurve) of
+/// `Option<T>` over the ticks between its first and last keyframe.
+///
+/// Fractional times return the pulse of the tick they fall within, if there is one.
+impl<T: PulseKeyframe<T>, K: Tick, S: CurveStorage<K, T>> BevyCurve<Option<T>>
+    for PulseCurve<T, K, S>
+{
+    fn domain(&self) -> Interval {
+        keyframe_domain(self, false)
+    }
+
+    fn sample_unchecked(&self, t: f32) -> Option<T> {
+        self.get_state_at(t as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::curve::{Curve as BevyCurve, Interval};
+
+    use crate::prelude::{
+        CurveTrait, LinearCurve, LinearKeyframe, PulseCurve, PulseKeyframe, SteppedCurve,
+        SteppedKeyframe,
+    };
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Radius(f32);
+
+    impl LinearKeyframe<Radius> for Radius {
+        fn lerp(&self, next_frame_state: &Radius, ratio: f64) -> Radius {
+            Radius(self.0 + (next_frame_state.0 - self.0) * ratio as f32)
+        }
+    }
+
+    impl SteppedKeyframe<Radius> for Radius {}
+
+    impl PulseKeyframe<Radius> for Radius {}
+
+    #[test]
+    fn test_bevy_curve() {
+        let mut linear = LinearCurve::new();
+        linear.insert_keyframe(10, Radius(1.0));
+        linear.insert_keyframe(20, Radius(2.0));
+        assert_eq!(linear.domain(), Interval::new(10.0, 20.0).unwrap());
+        assert_eq!(linear.sample(15.0), Some(Some(Radius(1.5))));
+        assert_eq!(linear.sample(25.0), None);
+        assert_eq!(linear.sample_clamped(25.0), Some(Radius(2.0)));
+
+        let doubled = linear
+            .by_ref()
+            .map(|radius| radius.map(|radius| radius.0 * 2.0));
+        assert_eq!(doubled.sample(12.5), Some(Some(2.5)));
+
+        // A curve without keyframes has no state instead of panicking
+        let empty = LinearCurve::<Radius>::new();
+        assert_eq!(empty.sample(5.0), Some(None));
+        assert_eq!(empty.sample_clamped(5.0), None);
+
+        let mut stepped = SteppedCurve::new();
+        stepped.insert_keyframe(10, Radius(1.0));
+        assert_eq!(
+            stepped.domain(),
+            Interval::new(10.0, f32::INFINITY).unwrap()
+        );
+        assert_eq!(stepped.sample(100.0), Some(Some(Radius(1.0))));
+
+        let mut pulse = PulseCurve::new();
+        pulse.insert_keyframe(10, Radius(1.0));
+        pulse.insert_keyframe(20, Radius(2.0));
+        assert_eq!(pulse.sample(10.5), Some(Some(Radius(1.0))));
+        assert_eq!(pulse.sample(15.0), Some(None));
+    }
+}
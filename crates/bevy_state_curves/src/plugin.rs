@@ -0,0 +1,234 @@
+use bevy::{
+    app::{App, Plugin, Update},
+    prelude::{
+        Commands, Component, DetectChanges, Entity, IntoSystemConfigs, Query, Ref, Res, Resource,
+        SystemSet,
+    },
+};
+
+use crate::{
+    curves::{CubicCurve, CurveTrait, LinearCurve, PhysicsCurve, PulseCurve, SteppedCurve},
+    events::add_keyframe_events,
+    keyframe_trait::{
+        CubicKeyframe, LinearKeyframe, PhysicsKeyframe, PulseKeyframe, SteppedKeyframe,
+    },
+    lifetime::{update_dormant, StateLifetime},
+    rewind::CurveRegistry,
+    storage::CurveStorage,
+    GameTick,
+};
+
+/// Plugin that drives all curves registered with [`RegisterCurve::register_curve`].
+///
+/// Every registered curve is sampled at the [`ViewedTick`] and the resulting state is written into the
+/// entities `T` component. If the curve has no state for that tick then the component is removed.
+///
+/// Entities with a [`StateLifetime`] have no state while they don't exist at the [`ViewedTick`] and are marked
+/// [`Dormant`](crate::lifetime::Dormant).
+///
+/// ```rust,ignore
+/// app.add_plugins(CurvesPlugin);
+/// app.register_curve::<LinearCurve<BodyAngle>>();
+/// ```
+pub struct CurvesPlugin;
+
+impl Plugin for CurvesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ViewedTick>();
+        app.init_resource::<CurveRegistry>();
+        app.configure_sets(Update, CurveSystems::SampleState);
+        app.add_systems(Update, update_dormant.in_set(CurveSystems::SampleState));
+    }
+}
+
+/// The [`GameTick`] that registered curves are sampled at.
+///
+/// Change this to move the state of every curve backed entity to that tick.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ViewedTick(pub GameTick);
+
+/// System sets used by the [`CurvesPlugin`]. Order your systems relative to these to read the freshly sampled state.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CurveSystems {
+    /// Advances the [`Timeline`](crate::timeline::Timeline). Runs in [`FixedUpdate`](bevy::app::FixedUpdate)
+    AdvanceTimeline,
+    /// Mirrors the [`Timeline`](crate::timeline::Timeline) into the [`ViewedTick`]. Runs in [`Update`] before [`CurveSystems::SampleState`]
+    SyncTimeline,
+    /// Loads [`CurveAsset`](crate::asset::CurveAsset)s into curves, see
+    /// [`RegisterCurveAsset`](crate::asset::RegisterCurveAsset). Runs in [`Update`] before [`CurveSystems::SampleState`]
+    #[cfg(feature = "asset")]
+    LoadAssets,
+    /// Samples every registered curve and writes the state into the entities components. Runs in [`Update`]
+    SampleState,
+    /// Prunes old keyframes from every curve registered with [`RetainCurve::retain_curve`](crate::retention::RetainCurve::retain_curve)
+    /// according to its [`CurveRetention`](crate::retention::CurveRetention). Runs in [`Update`] after [`CurveSystems::SampleState`]
+    EnforceRetention,
+    /// Writes curves to the [`ReplayRecorder`](crate::replay::ReplayRecorder). Runs in [`Last`](bevy::app::Last)
+    RecordReplay,
+}
+
+/// A curve that lives on an entity as a [`Component`] and whose state is also a [`Component`].
+///
+/// Implemented for all the curve types in this crate whenever their keyframe type is a component and their tick is
+/// [`GameTick`]. The [`ViewedTick`], keyframe events, change logs, rewinding and replays all work in [`GameTick`], so
+/// curves with another [`Tick`](crate::tick::Tick) can't be registered. They can still be inserted as components and
+/// mutated and sampled directly.
+pub trait ComponentCurve: CurveTrait<Self::State> + Component {
+    /// The state type that this curve stores and writes to the entity
+    type State: Component + Clone;
+}
+
+impl<T: LinearKeyframe<T> + Component, S: CurveStorage<GameTick, T> + Send + Sync + 'static>
+    ComponentCurve for LinearCurve<T, GameTick, S>
+{
+    type State = T;
+}
+
+impl<T: SteppedKeyframe<T> + Component, S: CurveStorage<GameTick, T> + Send + Sync + 'static>
+    ComponentCurve for SteppedCurve<T, GameTick, S>
+{
+    type State = T;
+}
+
+impl<T: PulseKeyframe<T> + Component, S: CurveStorage<GameTick, T> + Send + Sync + 'static>
+    ComponentCurve for PulseCurve<T, GameTick, S>
+{
+    type State = T;
+}
+
+impl<T: CubicKeyframe<T> + Component, S: CurveStorage<GameTick, T> + Send + Sync + 'static>
+    ComponentCurve for CubicCurve<T, GameTick, S>
+{
+    type State = T;
+}
+
+impl<T: PhysicsKeyframe<T> + Component, S: CurveStorage<GameTick, T> + Send + Sync + 'static>
+    ComponentCurve for PhysicsCurve<T, GameTick, S>
+{
+    type State = T;
+}
+
+/// Extension trait for [`App`] to register curves with the [`CurvesPlugin`]
+pub trait RegisterCurve {
+    /// Registers the curve `C` so that its state is written to its entity every time the [`ViewedTick`] or the curve changes.
+    ///
+    /// The curve is also added to the [`CurveRegistry`] so that it is rewound by [`RewindTo`](crate::rewind::RewindTo).
+    /// Registering the same curve more than once does nothing.
+    ///
+    /// This also adds the [`KeyframeInserted`](crate::events::KeyframeInserted) and
+    /// [`KeyframesRemoved`](crate::events::KeyframesRemoved) events for the curves state type, and the [`ViewedTick`]
+    /// resource if the [`CurvesPlugin`] hasn't added it yet.
+    fn register_curve<C: ComponentCurve>(&mut self) -> &mut Self;
+}
+
+impl RegisterCurve for App {
+    fn register_curve<C: ComponentCurve>(&mut self) -> &mut Self {
+        let newly_registered = self
+            .world_mut()
+            .get_resource_or_init::<CurveRegistry>()
+            .register::<C>();
+        if !newly_registered {
+            return self;
+        }
+        // The sampling system needs this even if the `CurvesPlugin` wasn't added
+        self.init_resource::<ViewedTick>();
+        // Rewinding the curve sends its keyframe events
+        add_keyframe_events::<C::State>(self);
+        self.add_systems(
+            Update,
+            sample_curve_state::<C>.in_set(CurveSystems::SampleState),
+        )
+    }
+}
+
+/// Samples every curve of type `C` at the [`ViewedTick`] and writes the state into the entity.
+///
+/// Curves are only sampled when either the [`ViewedTick`], the curve itself, or the entities [`StateLifetime`] changed.
+#[allow(clippy::type_complexity)]
+fn sample_curve_state<C: ComponentCurve>(
+    viewed_tick: Res<ViewedTick>,
+    mut curves: Query<(
+        Entity,
+        Ref<C>,
+        Option<Ref<StateLifetime>>,
+        Option<&mut C::State>,
+    )>,
+    mut commands: Commands,
+) {
+    let tick_changed = viewed_tick.is_changed();
+    for (entity, curve, lifetime, state) in curves.iter_mut() {
+        let lifetime_changed = lifetime
+            .as_ref()
+            .is_some_and(|lifetime| lifetime.is_changed());
+        if !tick_changed && !curve.is_changed() && !lifetime_changed {
+            continue;
+        }
+        let new_state = match lifetime {
+            Some(lifetime) => lifetime.get_state(&*curve, viewed_tick.0),
+            None => curve.get_state(viewed_tick.0),
+        };
+        match (new_state, state) {
+            (Some(new_state), Some(mut state)) => *state = new_state,
+            (Some(new_state), None) => {
+                commands.entity(entity).insert(new_state);
+            }
+            (None, Some(_)) => {
+                commands.entity(entity).remove::<C::State>();
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{App, Component};
+
+    use super::{CurvesPlugin, RegisterCurve, ViewedTick};
+    use crate::prelude::{CurveTrait, LinearCurve, LinearKeyframe};
+
+    #[derive(Component, Clone, Debug, PartialEq)]
+    struct Radius(f32);
+
+    impl LinearKeyframe<Radius> for Radius {
+        fn lerp(&self, next_frame_state: &Radius, ratio: f64) -> Radius {
+            Radius(self.0 + (next_frame_state.0 - self.0) * ratio as f32)
+        }
+    }
+
+    #[test]
+    fn test_state_written_and_removed() {
+        let mut app = App::new();
+        app.add_plugins(CurvesPlugin);
+        app.register_curve::<LinearCurve<Radius>>();
+
+        let mut curve = LinearCurve::new();
+        curve.insert_keyframe(10, Radius(1.0));
+        curve.insert_keyframe(20, Radius(2.0));
+        let entity = app.world_mut().spawn(curve).id();
+
+        app.update();
+        assert!(app.world().get::<Radius>(entity).is_none());
+
+        app.world_mut().resource_mut::<ViewedTick>().0 = 15;
+        app.update();
+        assert_eq!(app.world().get::<Radius>(entity), Some(&Radius(1.5)));
+
+        app.world_mut().resource_mut::<ViewedTick>().0 = 5;
+        app.update();
+        assert!(app.world().get::<Radius>(entity).is_none());
+    }
+
+    #[test]
+    fn test_register_without_plugin() {
+        let mut app = App::new();
+        app.register_curve::<LinearCurve<Radius>>();
+
+        let mut curve = LinearCurve::new();
+        curve.insert_keyframe(0, Radius(1.0));
+        let entity = app.world_mut().spawn(curve).id();
+
+        app.update();
+        assert_eq!(app.world().get::<Radius>(entity), Some(&Radius(1.0)));
+    }
+}
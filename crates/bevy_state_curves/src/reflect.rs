@@ -0,0 +1,259 @@
+use std::any::TypeId;
+
+use bevy::reflect::{FromReflect, FromType, PartialReflect, Reflect};
+
+use crate::{
+    curves::{CubicCurve, CurveTrait, LinearCurve, PhysicsCurve, PulseCurve, SteppedCurve},
+    error::{CurveError, ReflectCurveError},
+    keyframe_trait::{
+        CubicKeyframe, LinearKeyframe, PhysicsKeyframe, PulseKeyframe, SteppedKeyframe,
+    },
+    storage::CurveStorage,
+    tick::Tick,
+};
+
+/// Type data that gives access to a reflected curve without knowing its keyframe type at compile time.
+///
+/// Registered for every curve type under the "reflect" feature along with
+/// [`ReflectComponent`](bevy::ecs::reflect::ReflectComponent). Made for editors, inspectors and other tools that only
+/// have a `&dyn Reflect` of a curve.
+///
+/// ## Explanation:
+///
+/// - Ticks are passed as `f64` so that curves with any [`Tick`] type can be accessed the same way. See
+///   [`Tick::as_f64`] and [`Tick::split_fractional`].
+/// - Keyframes and states are passed as [`PartialReflect`] values of the curves keyframe type. See
+///   [`ReflectCurve::keyframe_type_id`].
+/// - Every function returns `None` or an error if the given curve isn't the type this was created for.
+/// - Keyframes are inserted with [`CurveTrait::try_insert_keyframe`], so frozen history can't be edited.
+///
+/// ```rust,ignore
+/// app.register_type::<LinearCurve<BodyAngle>>();
+///
+/// let registration = type_registry.get(curve.type_id()).unwrap();
+/// let reflect_curve = registration.data::<ReflectCurve>().unwrap();
+/// for tick in reflect_curve.keyframe_ticks(curve).unwrap() {
+///     println!("{tick}: {:?}", reflect_curve.get_state(curve, tick));
+/// }
+/// ```
+#[derive(Clone)]
+pub struct ReflectCurve {
+    keyframe_type_id: TypeId,
+    keyframe_ticks: fn(&dyn Reflect) -> Option<Vec<f64>>,
+    get_state: fn(&dyn Reflect, f64) -> Option<Box<dyn PartialReflect>>,
+    insert_keyframe:
+        fn(&mut dyn Reflect, f64, &dyn PartialReflect) -> Result<(), ReflectCurveError>,
+}
+
+impl ReflectCurve {
+    /// Creates the type data for the curve `C` storing keyframes of type `T`
+    fn new<C: CurveTrait<T, K> + Reflect, T: FromReflect, K: Tick>() -> ReflectCurve {
+        ReflectCurve {
+            keyframe_type_id: TypeId::of::<T>(),
+            keyframe_ticks: |curve| {
+                let curve = curve.downcast_ref::<C>()?;
+                Some(curve.range(..).map(|(tick, _)| tick.as_f64()).collect())
+            },
+            get_state: |curve, tick| {
+                let state = curve.downcast_ref::<C>()?.get_state_at(tick)?;
+                Some(Box::new(state))
+            },
+            insert_keyframe: |curve, tick, keyframe| {
+                let curve = curve
+                    .downcast_mut::<C>()
+                    .ok_or(ReflectCurveError::CurveTypeMismatch)?;
+                let Some((tick, 0.0)) = K::split_fractional(tick) else {
+                    return Err(ReflectCurveError::InvalidTick { tick });
+                };
+                let keyframe =
+                    T::from_reflect(keyframe).ok_or(ReflectCurveError::KeyframeTypeMismatch)?;
+                match curve.try_insert_keyframe(tick, keyframe) {
+                    Ok(_) => Ok(()),
+                    Err(CurveError::InsertBeforeFrozenTick { tick, frozen }) => {
+                        Err(ReflectCurveError::InsertBeforeFrozenTick {
+                            tick: tick.as_f64(),
+                            frozen: frozen.as_f64(),
+                        })
+                    }
+                    Err(_) => Err(ReflectCurveError::InvalidTick {
+                        tick: tick.as_f64(),
+                    }),
+                }
+            },
+        }
+    }
+
+    /// Returns the [`TypeId`] of the curves keyframe type
+    pub fn keyframe_type_id(&self) -> TypeId {
+        self.keyframe_type_id
+    }
+
+    /// Returns the tick of every keyframe in the curve in order
+    pub fn keyframe_ticks(&self, curve: &dyn Reflect) -> Option<Vec<f64>> {
+        (self.keyframe_ticks)(curve)
+    }
+
+    /// Samples the state of the curve at the given tick. See [`CurveTrait::get_state_at`]
+    pub fn get_state(&self, curve: &dyn Reflect, tick: f64) -> Option<Box<dyn PartialReflect>> {
+        (self.get_state)(curve, tick)
+    }
+
+    /// Inserts the keyframe into the curve at the given tick. See [`CurveTrait::try_insert_keyframe`]
+    ///
+    /// Fails if the keyframe isn't the curves keyframe type, the tick isn't a whole tick of the curves [`Tick`] type or
+    /// the tick is before the curve's frozen tick.
+    pub fn insert_keyframe(
+        &self,
+        curve: &mut dyn Reflect,
+        tick: f64,
+        keyframe: &dyn PartialReflect,
+    ) -> Result<(), ReflectCurveError> {
+        (self.insert_keyframe)(curve, tick, keyframe)
+    }
+}
+
+impl<T: LinearKeyframe<T> + FromReflect, K: Tick, S: CurveStorage<K, T>>
+    FromType<LinearCurve<T, K, S>> for ReflectCurve
+where
+    LinearCurve<T, K, S>: Reflect,
+{
+    fn from_type() -> Self {
+        ReflectCurve::new::<LinearCurve<T, K, S>, T, K>()
+    }
+}
+
+impl<T: SteppedKeyframe<T> + FromReflect, K: Tick, S: CurveStorage<K, T>>
+    FromType<SteppedCurve<T, K, S>> for ReflectCurve
+where
+    SteppedCurve<T, K, S>: Reflect,
+{
+    fn from_type() -> Self {
+        ReflectCurve::new::<SteppedCurve<T, K, S>, T, K>()
+    }
+}
+
+impl<T: PulseKeyframe<T> + FromReflect, K: Tick, S: CurveStorage<K, T>>
+    FromType<PulseCurve<T, K, S>> for ReflectCurve
+where
+    PulseCurve<T, K, S>: Reflect,
+{
+    fn from_type() -> Self {
+        ReflectCurve::new::<PulseCurve<T, K, S>, T, K>()
+    }
+}
+
+impl<T: CubicKeyframe<T> + FromReflect, K: Tick, S: CurveStorage<K, T>>
+    FromType<CubicCurve<T, K, S>> for ReflectCurve
+where
+    CubicCurve<T, K, S>: Reflect,
+{
+    fn from_type() -> Self {
+        ReflectCurve::new::<CubicCurve<T, K, S>, T, K>()
+    }
+}
+
+impl<T: PhysicsKeyframe<T> + FromReflect, K: Tick, S: CurveStorage<K, T>>
+    FromType<PhysicsCurve<T, K, S>> for ReflectCurve
+where
+    PhysicsCurve<T, K, S>: Reflect,
+{
+    fn from_type() -> Self {
+        ReflectCurve::new::<PhysicsCurve<T, K, S>, T, K>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::reflect::ReflectComponent,
+        prelude::{AppTypeRegistry, World},
+    };
+
+    use super::ReflectCurve;
+    use crate::prelude::{CurveTrait, LinearCurve, ReflectCurveError, SecondsTick, SteppedCurve};
+
+    #[test]
+    fn test_reflect_curve() {
+        let mut world = World::new();
+        let registry = AppTypeRegistry::default();
+        {
+            let mut registry = registry.write();
+            registry.register::<LinearCurve<f32>>();
+            registry.register::<SteppedCurve<u32, SecondsTick>>();
+        }
+        world.insert_resource(registry.clone());
+
+        let mut curve = LinearCurve::new();
+        curve.insert_keyframe(0, 0.0f32);
+        let entity = world.spawn(curve).id();
+
+        let registry = registry.read();
+        let registration = registry
+            .get(std::any::TypeId::of::<LinearCurve<f32>>())
+            .unwrap();
+        let reflect_component = registration.data::<ReflectComponent>().unwrap();
+        let reflect_curve = registration.data::<ReflectCurve>().unwrap();
+        assert_eq!(
+            reflect_curve.keyframe_type_id(),
+            std::any::TypeId::of::<f32>()
+        );
+
+        let mut entity_mut = world.entity_mut(entity);
+        let mut curve = reflect_component.reflect_mut(&mut entity_mut).unwrap();
+        assert_eq!(
+            reflect_curve.insert_keyframe(curve.as_reflect_mut(), 10.0, &4.0f32),
+            Ok(())
+        );
+        assert_eq!(
+            reflect_curve.insert_keyframe(curve.as_reflect_mut(), 20.0, &4u32),
+            Err(ReflectCurveError::KeyframeTypeMismatch)
+        );
+        assert_eq!(
+            reflect_curve.insert_keyframe(curve.as_reflect_mut(), 20.5, &4.0f32),
+            Err(ReflectCurveError::InvalidTick { tick: 20.5 })
+        );
+        curve
+            .downcast_mut::<LinearCurve<f32>>()
+            .unwrap()
+            .freeze_history(10);
+        assert_eq!(
+            reflect_curve.insert_keyframe(curve.as_reflect_mut(), 5.0, &4.0f32),
+            Err(ReflectCurveError::InsertBeforeFrozenTick {
+                tick: 5.0,
+                frozen: 10.0
+            })
+        );
+
+        let curve = reflect_component.reflect(world.entity(entity)).unwrap();
+        assert_eq!(reflect_curve.keyframe_ticks(curve), Some(vec![0.0, 10.0]));
+        let state = reflect_curve.get_state(curve, 5.0).unwrap();
+        assert_eq!(state.try_downcast_ref::<f32>(), Some(&2.0));
+        assert!(reflect_curve.get_state(curve, -1.0).is_none());
+        assert_eq!(
+            world.get::<LinearCurve<f32>>(entity).unwrap().get_state(5),
+            Some(2.0)
+        );
+
+        // Curves with other tick types are accessed with the same fractional ticks
+        let mut stepped = SteppedCurve::<u32, SecondsTick>::new();
+        let reflect_curve = registry
+            .get(std::any::TypeId::of::<SteppedCurve<u32, SecondsTick>>())
+            .unwrap()
+            .data::<ReflectCurve>()
+            .unwrap();
+        assert_eq!(
+            reflect_curve.insert_keyframe(&mut stepped, 0.5, &3u32),
+            Ok(())
+        );
+        assert_eq!(reflect_curve.keyframe_ticks(&stepped), Some(vec![0.5]));
+        assert_eq!(
+            reflect_curve
+                .get_state(&stepped, 1.0)
+                .and_then(|state| state.try_downcast_ref::<u32>().copied()),
+            Some(3)
+        );
+        assert!(reflect_curve
+            .keyframe_ticks(&LinearCurve::<f32>::new())
+            .is_none());
+    }
+}
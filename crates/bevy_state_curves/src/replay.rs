@@ -0,0 +1,813 @@
+use std::{
+    any::TypeId,
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    ops::{Bound, RangeBounds},
+    path::Path,
+};
+
+use bevy::{
+    app::{App, Last, Plugin},
+    prelude::{
+        DetectChanges, Entity, EventReader, IntoSystemConfigs, Local, Query, Ref, Res, ResMut,
+        Resource, World,
+    },
+};
+
+use crate::{
+    error::FormatError,
+    events::{KeyframeInserted, KeyframesRemoved},
+    format::{CurveRecord, KeyframeCodec, TimelineReader, TimelineRecord, TimelineWriter},
+    lifetime::StateLifetime,
+    plugin::{ComponentCurve, CurveSystems, CurvesPlugin, RegisterCurve, ViewedTick},
+    timeline::{Timeline, TimelinePlugin},
+    GameTick,
+};
+
+/// Loads a curve record into the curve on the entity, see [`load_curve`]
+type LoadFn = fn(&mut World, Entity, &CurveRecord) -> Result<(), FormatError>;
+/// Removes the keyframes within the ticks from the curve on the entity, see [`remove_curve_keyframes`]
+type RemoveFn = fn(&mut World, Entity, (Bound<GameTick>, Bound<GameTick>));
+/// Returns the tick of the last keyframe of the curve on the entity, see [`last_keyframe_tick`]
+type LastTickFn = fn(&World, Entity) -> Option<GameTick>;
+
+/// Plugin that records curves registered with [`ReplayCurve::replay_curve`] into a replay file and plays replay files
+/// back.
+///
+/// ## Explanation:
+///
+/// - Insert a [`ReplayRecorder`] to start recording. Every keyframe inserted or removed through
+///   [`CurveMut`](crate::events::CurveMut) or by a [`RewindTo`](crate::rewind::RewindTo) and every [`StateLifetime`]
+///   is written to it in the binary timeline format, see [`TimelineWriter`].
+/// - Load a replay with [`ReplayPlayer::load`]. Every recorded entity is spawned with its curves and lifetime and the
+///   [`Timeline`] plays it back from tick 0, with the simulation head fixed at the end of the replay.
+/// - Adds the [`CurvesPlugin`] and [`TimelinePlugin`] if they haven't been added yet.
+///
+/// Skip simulation systems while a replay is playing with the [`is_replaying`] run condition.
+///
+/// ```rust,ignore
+/// app.add_plugins(ReplayPlugin);
+/// app.replay_curve::<LinearCurve<BodyAngle>>();
+/// app.add_systems(FixedUpdate, simulate.run_if(not(is_replaying)));
+///
+/// // Recording
+/// app.insert_resource(ReplayRecorder::create("match.replay")?);
+/// // Playing it back
+/// ReplayPlayer::load_file(app.world_mut(), "match.replay")?;
+/// ```
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<CurvesPlugin>() {
+            app.add_plugins(CurvesPlugin);
+        }
+        if !app.is_plugin_added::<TimelinePlugin>() {
+            app.add_plugins(TimelinePlugin);
+        }
+        app.init_resource::<ReplayRegistry>();
+        app.add_systems(
+            Last,
+            (
+                record_lifetimes.in_set(CurveSystems::RecordReplay),
+                finish_recording.after(CurveSystems::RecordReplay),
+            ),
+        );
+    }
+}
+
+/// Extension trait for [`App`] to record and replay curves with the [`ReplayPlugin`]
+pub trait ReplayCurve {
+    /// Registers the curve `C`, see [`RegisterCurve::register_curve`], records its keyframes into the
+    /// [`ReplayRecorder`] and loads it from replays.
+    ///
+    /// Curves are identified in the replay by the [`KeyframeCodec::type_name`] of their keyframes. Use
+    /// [`ReplayCurve::replay_curve_as`] for curve types that share a keyframe type with another replayed curve.
+    fn replay_curve<C: ComponentCurve>(&mut self) -> &mut Self
+    where
+        C::State: KeyframeCodec;
+
+    /// Same as [`ReplayCurve::replay_curve`] but identifies the curve `C` in the replay by the name. Like
+    /// [`KeyframeCodec::type_name`] it must stay the same for as long as old replays should be loadable.
+    ///
+    /// Panics if the name is already used by another curve type.
+    fn replay_curve_as<C: ComponentCurve>(&mut self, name: impl Into<String>) -> &mut Self
+    where
+        C::State: KeyframeCodec;
+}
+
+impl ReplayCurve for App {
+    fn replay_curve<C: ComponentCurve>(&mut self) -> &mut Self
+    where
+        C::State: KeyframeCodec,
+    {
+        self.replay_curve_as::<C>(C::State::type_name())
+    }
+
+    fn replay_curve_as<C: ComponentCurve>(&mut self, name: impl Into<String>) -> &mut Self
+    where
+        C::State: KeyframeCodec,
+    {
+        self.register_curve::<C>();
+        let name = name.into();
+        let mut registry = self.world_mut().get_resource_or_init::<ReplayRegistry>();
+        if registry.curves.contains_key(&TypeId::of::<C>()) {
+            return self;
+        }
+        if registry.names.contains_key(&name) {
+            panic!("the replay name {name} is already used by another curve type");
+        }
+        registry.names.insert(name.clone(), TypeId::of::<C>());
+        registry.curves.insert(
+            TypeId::of::<C>(),
+            ReplayFns {
+                name,
+                load: load_curve::<C>,
+                remove: remove_curve_keyframes::<C>,
+                last_tick: last_keyframe_tick::<C>,
+            },
+        );
+        self.add_systems(
+            Last,
+            record_curve_keyframes::<C>.in_set(CurveSystems::RecordReplay),
+        )
+    }
+}
+
+/// The curve types registered with [`ReplayCurve::replay_curve`] and the names they are written to the replay with
+#[derive(Resource, Default, Clone)]
+struct ReplayRegistry {
+    curves: HashMap<TypeId, ReplayFns>,
+    names: HashMap<String, TypeId>,
+}
+
+impl ReplayRegistry {
+    /// Returns the functions of the curve type written to the replay with the name
+    fn get(&self, name: &str) -> Option<&ReplayFns> {
+        self.curves.get(self.names.get(name)?)
+    }
+}
+
+/// The name and functions of a curve type registered with [`ReplayCurve::replay_curve`]
+#[derive(Clone)]
+struct ReplayFns {
+    name: String,
+    load: LoadFn,
+    remove: RemoveFn,
+    last_tick: LastTickFn,
+}
+
+/// Records a replay while it is inserted as a resource. See [`ReplayPlugin`].
+///
+/// ## Explanation:
+///
+/// - Curves and lifetimes that already exist when the recorder is inserted are written in full, afterwards only
+///   keyframes inserted or removed through [`CurveMut`](crate::events::CurveMut) or by a
+///   [`RewindTo`](crate::rewind::RewindTo) and changed lifetimes are written.
+/// - Keyframes are buffered and written once `batch_size` keyframes of a curve type are waiting, so that every curve
+///   record holds many keyframes.
+/// - Removals are written straight away as removal records, so the future discarded by a rewind doesn't show up in
+///   the replay. Buffered keyframes within the removed ticks are dropped.
+/// - Call [`ReplayRecorder::stop`] to write everything that is buffered and finish the file at the end of the frame.
+/// - Remove a recorder before inserting the next one. A recorder that replaces another one in place isn't seen as a
+///   new recording, so existing curves and lifetimes aren't written to it.
+#[derive(Resource)]
+pub struct ReplayRecorder {
+    writer: Option<TimelineWriter<Box<dyn Write + Send + Sync>>>,
+    /// How many keyframes of a curve type are buffered before they are written
+    pub batch_size: usize,
+    stopping: bool,
+    error: Option<FormatError>,
+}
+
+impl ReplayRecorder {
+    /// Creates a recorder writing the replay to the writer
+    pub fn new(writer: impl Write + Send + Sync + 'static) -> Result<ReplayRecorder, FormatError> {
+        let writer: Box<dyn Write + Send + Sync> = Box::new(writer);
+        Ok(ReplayRecorder {
+            writer: Some(TimelineWriter::new(writer)?),
+            batch_size: 4096,
+            stopping: false,
+            error: None,
+        })
+    }
+
+    /// Creates a recorder writing the replay to a new file at the path
+    pub fn create(path: impl AsRef<Path>) -> Result<ReplayRecorder, FormatError> {
+        ReplayRecorder::new(BufWriter::new(File::create(path)?))
+    }
+
+    /// Writes every buffered keyframe and finishes the replay at the end of the frame
+    pub fn stop(&mut self) {
+        self.stopping = true;
+    }
+
+    /// Returns true until the recorder has been stopped or failed to write
+    pub fn is_recording(&self) -> bool {
+        self.writer.is_some()
+    }
+
+    /// Returns the error that stopped the recording, if writing failed
+    pub fn error(&self) -> Option<&FormatError> {
+        self.error.as_ref()
+    }
+
+    /// Runs the write, stopping the recording if it fails
+    fn write(
+        &mut self,
+        write: impl FnOnce(&mut TimelineWriter<Box<dyn Write + Send + Sync>>) -> Result<(), FormatError>,
+    ) {
+        let Some(writer) = self.writer.as_mut() else {
+            return;
+        };
+        if let Err(error) = write(writer) {
+            self.writer = None;
+            self.error = Some(error);
+        }
+    }
+}
+
+/// Keyframes of type `T` waiting to be written by a [`ReplayRecorder`]
+struct PendingKeyframes<T> {
+    curves: HashMap<Entity, BTreeMap<GameTick, T>>,
+    len: usize,
+}
+
+impl<T> Default for PendingKeyframes<T> {
+    fn default() -> Self {
+        PendingKeyframes {
+            curves: HashMap::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<T> PendingKeyframes<T> {
+    fn remove(&mut self, entity: Entity, ticks: (Bound<GameTick>, Bound<GameTick>)) {
+        let Some(keyframes) = self.curves.get_mut(&entity) else {
+            return;
+        };
+        let len = keyframes.len();
+        keyframes.retain(|tick, _| !ticks.contains(tick));
+        self.len -= len - keyframes.len();
+    }
+
+    fn insert(&mut self, entity: Entity, tick: GameTick, keyframe: T) {
+        if self
+            .curves
+            .entry(entity)
+            .or_default()
+            .insert(tick, keyframe)
+            .is_none()
+        {
+            self.len += 1;
+        }
+    }
+}
+
+/// Buffers the keyframes inserted into curves of type `C` and writes them, and the removed keyframes, to the
+/// [`ReplayRecorder`]
+fn record_curve_keyframes<C: ComponentCurve>(
+    recorder: Option<ResMut<ReplayRecorder>>,
+    registry: Res<ReplayRegistry>,
+    mut inserted: EventReader<KeyframeInserted<C::State>>,
+    mut removed: EventReader<KeyframesRemoved<C::State>>,
+    curves: Query<(Entity, &C)>,
+    mut pending: Local<PendingKeyframes<C::State>>,
+) where
+    C::State: KeyframeCodec,
+{
+    let Some(mut recorder) = recorder.filter(|recorder| recorder.is_recording()) else {
+        inserted.clear();
+        removed.clear();
+        return;
+    };
+    if recorder.is_added() {
+        inserted.clear();
+        removed.clear();
+        // Keyframes buffered for a previous recorder that was replaced before writing them
+        *pending = PendingKeyframes::default();
+        for (entity, curve) in curves.iter() {
+            for (tick, keyframe) in curve.range(..) {
+                pending.insert(entity, *tick, keyframe.clone());
+            }
+        }
+    }
+    let name = &registry.curves[&TypeId::of::<C>()].name;
+    // Inserts and removals are sent as separate events that can't be ordered against each other. Writing the removals
+    // first and then the inserted keyframes that are still on the curve at the end of the frame gives the same curve:
+    // a keyframe within a removed range that still exists must have been inserted after the removal.
+    for event in removed.read() {
+        pending.remove(event.entity, event.ticks);
+        recorder
+            .write(|writer| writer.write_named_removal(event.entity.to_bits(), name, event.ticks));
+    }
+    for event in inserted.read() {
+        // The keyframe is read from the curve so the recording holds its value at the end of the frame
+        let Some(keyframe) = curves
+            .get(event.entity)
+            .ok()
+            .and_then(|(_, curve)| curve.get_keyframe(event.tick))
+        else {
+            continue;
+        };
+        pending.insert(event.entity, event.tick, keyframe.clone());
+    }
+
+    if pending.len < recorder.batch_size.max(1) && !recorder.stopping {
+        return;
+    }
+    pending.len = 0;
+    for (entity, keyframes) in pending.curves.drain() {
+        recorder
+            .write(|writer| writer.write_named_keyframes(entity.to_bits(), name, keyframes.iter()));
+    }
+}
+
+/// Writes every changed [`StateLifetime`] to the [`ReplayRecorder`]
+fn record_lifetimes(
+    recorder: Option<ResMut<ReplayRecorder>>,
+    lifetimes: Query<(Entity, Ref<StateLifetime>)>,
+) {
+    let Some(mut recorder) = recorder.filter(|recorder| recorder.is_recording()) else {
+        return;
+    };
+    let record_all = recorder.is_added();
+    for (entity, lifetime) in lifetimes.iter() {
+        if record_all || lifetime.is_changed() {
+            recorder.write(|writer| writer.write_lifetime(entity.to_bits(), &lifetime));
+        }
+    }
+}
+
+/// Finishes the replay once the [`ReplayRecorder`] has been stopped and every curve type has written its keyframes
+fn finish_recording(recorder: Option<ResMut<ReplayRecorder>>) {
+    let Some(mut recorder) = recorder.filter(|recorder| recorder.stopping) else {
+        return;
+    };
+    if let Some(writer) = recorder.writer.take() {
+        if let Err(error) = writer.finish() {
+            recorder.error = Some(error);
+        }
+    }
+}
+
+/// Inserts the curve record into the curve `C` on the entity, creating the curve if the entity doesn't have one yet
+fn load_curve<C: ComponentCurve>(
+    world: &mut World,
+    entity: Entity,
+    record: &CurveRecord,
+) -> Result<(), FormatError>
+where
+    C::State: KeyframeCodec,
+{
+    let keyframes = record.named_keyframes::<C::State>()?;
+    let mut entity = world.entity_mut(entity);
+    if let Some(mut curve) = entity.get_mut::<C>() {
+        for (tick, keyframe) in keyframes {
+            curve.insert_keyframe(tick, keyframe);
+        }
+        return Ok(());
+    }
+    let mut curve = C::new();
+    for (tick, keyframe) in keyframes {
+        curve.insert_keyframe(tick, keyframe);
+    }
+    entity.insert(curve);
+    Ok(())
+}
+
+/// Removes the keyframes within the ticks from the curve `C` on the entity
+fn remove_curve_keyframes<C: ComponentCurve>(
+    world: &mut World,
+    entity: Entity,
+    ticks: (Bound<GameTick>, Bound<GameTick>),
+) {
+    let Some(mut curve) = world.get_mut::<C>(entity) else {
+        return;
+    };
+    let removed = curve
+        .range(ticks)
+        .map(|(tick, _)| *tick)
+        .collect::<Vec<GameTick>>();
+    for tick in removed {
+        curve.remove_keyframe(tick);
+    }
+}
+
+/// Returns the tick of the last keyframe of the curve `C` on the entity
+fn last_keyframe_tick<C: ComponentCurve>(world: &World, entity: Entity) -> Option<GameTick> {
+    world
+        .get::<C>(entity)?
+        .last_keyframe()
+        .map(|(tick, _)| *tick)
+}
+
+/// A replay loaded by [`ReplayPlayer::load`], inserted as a resource while it plays. See [`ReplayPlugin`].
+#[derive(Resource, Debug, Default)]
+pub struct ReplayPlayer {
+    entities: HashMap<u64, Entity>,
+    end_tick: GameTick,
+    skipped: usize,
+}
+
+impl ReplayPlayer {
+    /// Loads the replay into the world and starts playing it.
+    ///
+    /// Spawns an entity for every recorded entity with its curves and [`StateLifetime`], inserts the [`ReplayPlayer`]
+    /// resource and sets the [`Timeline`] to play from tick 0 without simulating. Curves of types that weren't
+    /// registered with [`ReplayCurve::replay_curve`] are skipped. If loading fails the entities spawned so far are
+    /// despawned again and the world is left as it was.
+    pub fn load(world: &mut World, reader: impl Read) -> Result<(), FormatError> {
+        let registry = world
+            .get_resource::<ReplayRegistry>()
+            .cloned()
+            .unwrap_or_default();
+        let reader = TimelineReader::new(reader)?;
+        let mut player = ReplayPlayer::default();
+        if let Err(error) = player.load_records(world, &registry, reader) {
+            for &entity in player.entities.values() {
+                world.despawn(entity);
+            }
+            return Err(error);
+        }
+        // Found once everything is loaded since removals can take keyframes away again
+        for &entity in player.entities.values() {
+            for fns in registry.curves.values() {
+                if let Some(tick) = (fns.last_tick)(world, entity) {
+                    player.end_tick = player.end_tick.max(tick);
+                }
+            }
+            if let Some(lifetime) = world.get::<StateLifetime>(entity) {
+                player.end_tick = player
+                    .end_tick
+                    .max(lifetime.despawned.unwrap_or(lifetime.spawned));
+            }
+        }
+
+        let mut timeline = world.get_resource_or_insert_with(Timeline::default);
+        timeline.simulating = false;
+        timeline.set_simulation_tick(player.end_tick);
+        timeline.scrub_to(0);
+        timeline.play();
+        world.get_resource_or_init::<ViewedTick>().0 = 0;
+        world.insert_resource(player);
+        Ok(())
+    }
+
+    /// Spawns an entity for every recorded entity and loads the records into it, stopping at the first error
+    fn load_records(
+        &mut self,
+        world: &mut World,
+        registry: &ReplayRegistry,
+        reader: TimelineReader<impl Read>,
+    ) -> Result<(), FormatError> {
+        for record in reader {
+            match record? {
+                TimelineRecord::Curve(record) => {
+                    let Some(fns) = registry.get(record.type_name()) else {
+                        self.skipped += 1;
+                        continue;
+                    };
+                    let entity = self.entity_or_spawn(world, record.id());
+                    (fns.load)(world, entity, &record)?;
+                }
+                TimelineRecord::Lifetime { id, lifetime } => {
+                    let entity = self.entity_or_spawn(world, id);
+                    world.entity_mut(entity).insert(lifetime);
+                }
+                TimelineRecord::Removal {
+                    id,
+                    type_name,
+                    ticks,
+                } => {
+                    let (Some(fns), Some(&entity)) =
+                        (registry.get(&type_name), self.entities.get(&id))
+                    else {
+                        continue;
+                    };
+                    (fns.remove)(world, entity, ticks);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads the replay file at the path, see [`ReplayPlayer::load`]
+    pub fn load_file(world: &mut World, path: impl AsRef<Path>) -> Result<(), FormatError> {
+        ReplayPlayer::load(world, BufReader::new(File::open(path)?))
+    }
+
+    fn entity_or_spawn(&mut self, world: &mut World, id: u64) -> Entity {
+        *self
+            .entities
+            .entry(id)
+            .or_insert_with(|| world.spawn_empty().id())
+    }
+
+    /// Returns the entity that was spawned for the entity recorded with the given id, the bits of the recorded
+    /// [`Entity`]
+    pub fn entity(&self, recorded: Entity) -> Option<Entity> {
+        self.entities.get(&recorded.to_bits()).copied()
+    }
+
+    /// Returns the last tick with a keyframe or lifetime change in the replay
+    pub fn end_tick(&self) -> GameTick {
+        self.end_tick
+    }
+
+    /// Returns how many curves were skipped because their type wasn't registered
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+}
+
+/// Run condition that is true while a [`ReplayPlayer`] is loaded. Use `not(is_replaying)` to skip simulation
+/// systems during playback
+pub fn is_replaying(player: Option<Res<ReplayPlayer>>) -> bool {
+    player.is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Write,
+        sync::{Arc, Mutex},
+    };
+
+    use bevy::{
+        ecs::{system::RunSystemOnce, world::Command},
+        prelude::{App, Component},
+    };
+
+    use super::{is_replaying, ReplayCurve, ReplayPlayer, ReplayPlugin, ReplayRecorder};
+    use crate::{
+        error::FormatError,
+        prelude::{
+            CurveMut, CurveTrait, KeyframeCodec, LinearCurve, LinearKeyframe, RewindTo,
+            StateLifetime, SteppedCurve, SteppedKeyframe, Timeline, ViewedTick,
+        },
+    };
+
+    #[derive(Component, Clone, Debug, PartialEq)]
+    struct Height(f32);
+
+    impl LinearKeyframe<Height> for Height {
+        fn lerp(&self, next: &Height, ratio: f64) -> Height {
+            Height(LinearKeyframe::lerp(&self.0, &next.0, ratio))
+        }
+    }
+
+    impl SteppedKeyframe<Height> for Height {}
+
+    impl KeyframeCodec for Height {
+        fn type_name() -> String {
+            "Height".into()
+        }
+
+        fn encode(&self, buf: &mut Vec<u8>) {
+            self.0.encode(buf);
+        }
+
+        fn decode(version: u32, bytes: &mut &[u8]) -> Result<Self, FormatError> {
+            Ok(Height(f32::decode(version, bytes)?))
+        }
+    }
+
+    /// A writer that can be read back after the recorder is done with it
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn replay_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(ReplayPlugin);
+        app.replay_curve::<LinearCurve<Height>>();
+        app
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let mut app = replay_app();
+        let mut curve = LinearCurve::new();
+        curve.insert_keyframe(0, Height(0.0));
+        let ball = app.world_mut().spawn((curve, StateLifetime::new(0))).id();
+
+        let buffer = SharedBuffer::default();
+        let mut recorder = ReplayRecorder::new(buffer.clone()).unwrap();
+        recorder.batch_size = 2;
+        app.insert_resource(recorder);
+        app.update();
+
+        for tick in 1..=5 {
+            app.world_mut()
+                .run_system_once(move |mut curves: CurveMut<LinearCurve<Height>>| {
+                    curves.insert_keyframe(ball, tick * 10, Height(tick as f32));
+                })
+                .unwrap();
+            app.update();
+        }
+        app.world_mut()
+            .get_mut::<StateLifetime>(ball)
+            .unwrap()
+            .despawn_at(60);
+        app.world_mut().resource_mut::<ReplayRecorder>().stop();
+        app.update();
+        let recorder = app.world().resource::<ReplayRecorder>();
+        assert!(!recorder.is_recording());
+        assert!(recorder.error().is_none());
+
+        let mut app = replay_app();
+        assert!(!app.world_mut().run_system_once(is_replaying).unwrap());
+        let bytes = buffer.0.lock().unwrap().clone();
+        ReplayPlayer::load(app.world_mut(), bytes.as_slice()).unwrap();
+
+        assert!(app.world_mut().run_system_once(is_replaying).unwrap());
+        let player = app.world().resource::<ReplayPlayer>();
+        assert_eq!(player.end_tick(), 60);
+        assert_eq!(player.skipped(), 0);
+        let loaded = player.entity(ball).unwrap();
+        let curve = app.world().get::<LinearCurve<Height>>(loaded).unwrap();
+        assert_eq!(curve.range(..).count(), 6);
+        assert_eq!(curve.get_state(45), Some(Height(4.5)));
+        assert_eq!(
+            app.world().get::<StateLifetime>(loaded).unwrap().despawned,
+            Some(60)
+        );
+
+        let mut timeline = app.world_mut().resource_mut::<Timeline>();
+        assert!(!timeline.simulating);
+        assert_eq!(timeline.simulation_tick(), 60);
+        timeline.pause();
+        timeline.scrub_to(25);
+        app.update();
+        assert_eq!(app.world().resource::<ViewedTick>().0, 25);
+        assert_eq!(app.world().get::<Height>(loaded), Some(&Height(2.5)));
+
+        // The entity doesn't exist after it was despawned
+        app.world_mut().resource_mut::<Timeline>().scrub_to(60);
+        app.update();
+        assert_eq!(app.world().get::<Height>(loaded), None);
+        assert!(app.world().get_entity(loaded).is_ok());
+    }
+
+    #[test]
+    fn test_record_rewind_and_replay() {
+        let mut app = replay_app();
+        let ball = app.world_mut().spawn(LinearCurve::<Height>::new()).id();
+
+        let buffer = SharedBuffer::default();
+        let mut recorder = ReplayRecorder::new(buffer.clone()).unwrap();
+        recorder.batch_size = 2;
+        app.insert_resource(recorder);
+        app.update();
+
+        for tick in 0..=5 {
+            app.world_mut()
+                .run_system_once(move |mut curves: CurveMut<LinearCurve<Height>>| {
+                    curves.insert_keyframe(ball, tick * 10, Height(tick as f32));
+                })
+                .unwrap();
+            app.update();
+        }
+        // Branch off at tick 25, discarding the keyframes on 30, 40 and 50, and simulate a different future
+        RewindTo(25).apply(app.world_mut());
+        app.world_mut()
+            .run_system_once(move |mut curves: CurveMut<LinearCurve<Height>>| {
+                curves.insert_keyframe(ball, 30, Height(10.0));
+            })
+            .unwrap();
+        app.world_mut().resource_mut::<ReplayRecorder>().stop();
+        app.update();
+        assert!(app.world().resource::<ReplayRecorder>().error().is_none());
+
+        let mut app = replay_app();
+        let bytes = buffer.0.lock().unwrap().clone();
+        ReplayPlayer::load(app.world_mut(), bytes.as_slice()).unwrap();
+        let player = app.world().resource::<ReplayPlayer>();
+        assert_eq!(player.end_tick(), 30);
+        let loaded = player.entity(ball).unwrap();
+        let curve = app.world().get::<LinearCurve<Height>>(loaded).unwrap();
+        assert_eq!(
+            curve.range(..).map(|(tick, _)| *tick).collect::<Vec<_>>(),
+            vec![0, 10, 20, 25, 30]
+        );
+        assert_eq!(curve.get_state(25), Some(Height(2.5)));
+        assert_eq!(curve.get_state(30), Some(Height(10.0)));
+    }
+
+    #[test]
+    fn test_curves_sharing_keyframes() {
+        let mut app = replay_app();
+        app.replay_curve_as::<SteppedCurve<Height>>("SteppedHeight");
+        let mut linear = LinearCurve::new();
+        linear.insert_keyframe(0, Height(1.0));
+        let mut stepped = SteppedCurve::new();
+        stepped.insert_keyframe(0, Height(2.0));
+        let ball = app.world_mut().spawn(linear).id();
+        let block = app.world_mut().spawn(stepped).id();
+
+        let buffer = SharedBuffer::default();
+        app.insert_resource(ReplayRecorder::new(buffer.clone()).unwrap());
+        app.update();
+        app.world_mut().resource_mut::<ReplayRecorder>().stop();
+        app.update();
+
+        let mut app = replay_app();
+        app.replay_curve_as::<SteppedCurve<Height>>("SteppedHeight");
+        let bytes = buffer.0.lock().unwrap().clone();
+        ReplayPlayer::load(app.world_mut(), bytes.as_slice()).unwrap();
+        let player = app.world().resource::<ReplayPlayer>();
+        let (ball, block) = (player.entity(ball).unwrap(), player.entity(block).unwrap());
+        assert!(app.world().get::<SteppedCurve<Height>>(ball).is_none());
+        assert_eq!(
+            app.world()
+                .get::<LinearCurve<Height>>(ball)
+                .unwrap()
+                .get_state(0),
+            Some(Height(1.0))
+        );
+        assert!(app.world().get::<LinearCurve<Height>>(block).is_none());
+        assert_eq!(
+            app.world()
+                .get::<SteppedCurve<Height>>(block)
+                .unwrap()
+                .get_state(0),
+            Some(Height(2.0))
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_replay_name_used_twice() {
+        replay_app().replay_curve_as::<SteppedCurve<Height>>("Height");
+    }
+
+    #[test]
+    fn test_failed_load_despawns_entities() {
+        let mut app = replay_app();
+        for height in 0..3 {
+            let mut curve = LinearCurve::new();
+            curve.insert_keyframe(0, Height(height as f32));
+            app.world_mut().spawn(curve);
+        }
+        let buffer = SharedBuffer::default();
+        app.insert_resource(ReplayRecorder::new(buffer.clone()).unwrap());
+        app.update();
+        app.world_mut().resource_mut::<ReplayRecorder>().stop();
+        app.update();
+
+        // Cut off in the middle of the last curve record
+        let bytes = buffer.0.lock().unwrap().clone();
+        let mut app = replay_app();
+        let entities = app.world().entities().len();
+        assert!(ReplayPlayer::load(app.world_mut(), &bytes[..bytes.len() - 3]).is_err());
+        assert_eq!(app.world().entities().len(), entities);
+        assert!(app.world().get_resource::<ReplayPlayer>().is_none());
+    }
+
+    #[test]
+    fn test_new_recorder_drops_buffered_keyframes() {
+        let mut app = replay_app();
+        let ball = app.world_mut().spawn(LinearCurve::<Height>::new()).id();
+        let mut recorder = ReplayRecorder::new(SharedBuffer::default()).unwrap();
+        recorder.batch_size = 100;
+        app.insert_resource(recorder);
+        app.update();
+        app.world_mut()
+            .run_system_once(move |mut curves: CurveMut<LinearCurve<Height>>| {
+                curves.insert_keyframe(ball, 10, Height(1.0));
+            })
+            .unwrap();
+        app.update();
+
+        // The keyframe is still buffered when the recording is dropped and the ball despawned
+        app.world_mut().remove_resource::<ReplayRecorder>();
+        app.world_mut().despawn(ball);
+        app.update();
+        let buffer = SharedBuffer::default();
+        app.insert_resource(ReplayRecorder::new(buffer.clone()).unwrap());
+        app.update();
+        app.world_mut().resource_mut::<ReplayRecorder>().stop();
+        app.update();
+
+        let mut app = replay_app();
+        let bytes = buffer.0.lock().unwrap().clone();
+        ReplayPlayer::load(app.world_mut(), bytes.as_slice()).unwrap();
+        assert!(app
+            .world()
+            .resource::<ReplayPlayer>()
+            .entity(ball)
+            .is_none());
+    }
+}
@@ -0,0 +1,603 @@
+use std::{
+    any::TypeId,
+    ops::{Bound, RangeBounds},
+};
+
+use bevy::{
+    app::App,
+    prelude::{Commands, Component, OnInsert, OnReplace, Query, Resource, Trigger},
+    utils::HashSet,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(all(feature = "type_path", not(feature = "reflect")))]
+use bevy::reflect::TypePath;
+
+#[cfg(feature = "reflect")]
+use bevy::{ecs::reflect::ReflectComponent, reflect::Reflect};
+
+use crate::{
+    curves::CurveTrait,
+    plugin::{ComponentCurve, RegisterCurve},
+    storage::is_valid_range,
+    GameTick,
+};
+
+/// A single change to a curve, as recorded by a [`CurveChangeLog`] and sent in a [`CurvePatch`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(all(feature = "type_path", not(feature = "reflect")), derive(TypePath))]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+pub enum PatchOp<T> {
+    /// The keyframe was inserted on the tick, replacing any keyframe already there
+    Insert {
+        /// The tick the keyframe was inserted on
+        tick: GameTick,
+        /// The inserted keyframe
+        keyframe: T,
+    },
+    /// Every keyframe within the ticks was removed. A single keyframe is removed with an inclusive range of one tick
+    /// while truncations, such as [`CurveTrait::remove_future_keyframes`], use an unbounded range.
+    Remove {
+        /// The range of ticks that keyframes were removed from
+        ticks: (Bound<GameTick>, Bound<GameTick>),
+    },
+}
+
+impl<T> PatchOp<T> {
+    /// Returns true if applying `self` completely overrides the effect of `other`, so `other` doesn't need to be sent
+    fn supersedes(&self, other: &PatchOp<T>) -> bool {
+        match (self, other) {
+            (PatchOp::Insert { tick, .. }, PatchOp::Insert { tick: other, .. }) => tick == other,
+            (PatchOp::Insert { tick, .. }, PatchOp::Remove { ticks }) => {
+                *ticks == (Bound::Included(*tick), Bound::Included(*tick))
+            }
+            (PatchOp::Remove { ticks }, PatchOp::Insert { tick, .. }) => ticks.contains(tick),
+            (PatchOp::Remove { ticks }, PatchOp::Remove { ticks: other }) => {
+                range_covers(ticks, other)
+            }
+        }
+    }
+
+    /// Applies this op to the curve
+    fn apply(&self, curve: &mut impl CurveTrait<T>)
+    where
+        T: Clone,
+    {
+        match self {
+            PatchOp::Insert { tick, keyframe } => {
+                curve.insert_keyframe(*tick, keyframe.clone());
+            }
+            PatchOp::Remove {
+                ticks: (Bound::Included(tick), Bound::Unbounded),
+            } => curve.remove_future_keyframes(tick),
+            PatchOp::Remove {
+                ticks: (Bound::Unbounded, Bound::Included(tick)),
+            } => curve.remove_past_keyframes(tick),
+            PatchOp::Remove { ticks } => {
+                let removed = curve
+                    .range(*ticks)
+                    .map(|(tick, _)| *tick)
+                    .collect::<Vec<GameTick>>();
+                for tick in removed {
+                    curve.remove_keyframe(tick);
+                }
+            }
+        }
+    }
+}
+
+/// Returns true if every tick in `other` is also in `range`
+fn range_covers(
+    range: &(Bound<GameTick>, Bound<GameTick>),
+    other: &(Bound<GameTick>, Bound<GameTick>),
+) -> bool {
+    let start_covered = match (range.0, other.0) {
+        (Bound::Unbounded, _) => true,
+        (_, Bound::Unbounded) => false,
+        (Bound::Included(start), Bound::Included(other) | Bound::Excluded(other)) => start <= other,
+        (Bound::Excluded(start), Bound::Included(other)) => start < other,
+        (Bound::Excluded(start), Bound::Excluded(other)) => start <= other,
+    };
+    let end_covered = match (range.1, other.1) {
+        (Bound::Unbounded, _) => true,
+        (_, Bound::Unbounded) => false,
+        (Bound::Included(end), Bound::Included(other) | Bound::Excluded(other)) => end >= other,
+        (Bound::Excluded(end), Bound::Included(other)) => end > other,
+        (Bound::Excluded(end), Bound::Excluded(other)) => end >= other,
+    };
+    start_covered && end_covered
+}
+
+/// A compact set of changes to a curve that brings a client from `base_revision` up to `revision`.
+///
+/// ## Explanation:
+///
+/// - Every op carries the revision of the [`CurveChangeLog`] it was recorded at.
+/// - [`CurvePatch::apply`] only applies ops newer than the revision the client has already applied, so applying the
+///   same patch twice, or an older overlapping patch, does nothing.
+/// - If the client is older than `base_revision` then it has missed changes and needs a [`CurvePatch::snapshot`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(all(feature = "type_path", not(feature = "reflect")), derive(TypePath))]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+pub struct CurvePatch<T> {
+    /// The revision that a client must have applied for this patch to apply cleanly
+    pub base_revision: u64,
+    /// The revision the client is at after applying this patch
+    pub revision: u64,
+    /// The changes to apply, oldest first, along with the revision they were recorded at
+    pub ops: Vec<(u64, PatchOp<T>)>,
+}
+
+impl<T: Clone> CurvePatch<T> {
+    /// Creates a patch that replaces the entire curve on the client with the given curve, as of `revision`.
+    ///
+    /// Send this to new clients or clients that fell too far behind to be patched.
+    pub fn snapshot(curve: &impl CurveTrait<T>, revision: u64) -> CurvePatch<T> {
+        let mut ops = vec![(
+            revision,
+            PatchOp::Remove {
+                ticks: (Bound::Unbounded, Bound::Unbounded),
+            },
+        )];
+        ops.extend(curve.range(..).map(|(tick, keyframe)| {
+            (
+                revision,
+                PatchOp::Insert {
+                    tick: *tick,
+                    keyframe: keyframe.clone(),
+                },
+            )
+        }));
+        // Every op is newer than any revision the client could have applied so the snapshot always applies
+        CurvePatch {
+            base_revision: 0,
+            revision,
+            ops,
+        }
+    }
+
+    /// Applies the patch to the clients curve. `applied_revision` is the revision the client is currently at and is
+    /// updated to the revision of the patch.
+    ///
+    /// Returns false, without changing anything, if the client is missing changes from before this patch and needs a
+    /// snapshot instead, or if the patch is malformed, such as a removal whose range ends before it starts.
+    pub fn apply(&self, curve: &mut impl CurveTrait<T>, applied_revision: &mut u64) -> bool {
+        if self.revision <= *applied_revision {
+            return true;
+        }
+        if self.base_revision > *applied_revision {
+            return false;
+        }
+        // Patches come from the network so a bad range must not reach `CurveTrait::range`, which would panic
+        let malformed = self.ops.iter().any(|(_, op)| match op {
+            PatchOp::Remove { ticks } => !is_valid_range(ticks),
+            PatchOp::Insert { .. } => false,
+        });
+        if malformed {
+            return false;
+        }
+        for (_, op) in self
+            .ops
+            .iter()
+            .filter(|(revision, _)| *revision > *applied_revision)
+        {
+            op.apply(curve);
+        }
+        *applied_revision = self.revision;
+        true
+    }
+}
+
+/// Records every change made to the curve on the same entity so they can be replicated to clients as [`CurvePatch`]es.
+///
+/// Added to every entity with a curve registered with [`ReplicateCurve::replicate_curve`].
+///
+/// ## Explanation:
+///
+/// - Changes are recorded by [`CurveMut`](crate::events::CurveMut) and [`RewindTo`](crate::rewind::RewindTo) at the
+///   moment they are made.
+/// - Inserting the curve component, such as when a curve asset is reloaded or a scene is loaded, records the removal
+///   of every keyframe of the old curve and the insert of every keyframe of the new one.
+/// - Every other change to the curve component isn't recorded and makes clients drift from the server until they are
+///   sent a [`CurvePatch::snapshot`]. This includes mutating the component directly, such as with
+///   [`LinearCurve::simplify`](crate::curves::LinearCurve::simplify) or
+///   [`SteppedCurve::dedup`](crate::curves::SteppedCurve::dedup), and inserting keyframes through `ReflectCurve`.
+///
+/// ```rust,ignore
+/// app.replicate_curve::<LinearCurve<BodyAngle>>();
+/// commands.spawn(LinearCurve::<BodyAngle>::new());
+///
+/// // Every network tick, per client
+/// match change_log.patch_since(client.revision) {
+///     Some(patch) => client.send(patch),
+///     None => client.send(CurvePatch::snapshot(curve, change_log.revision())),
+/// }
+/// ```
+#[derive(Component, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(all(feature = "type_path", not(feature = "reflect")), derive(TypePath))]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct CurveChangeLog<T: Send + Sync + 'static> {
+    revision: u64,
+    compacted_revision: u64,
+    ops: Vec<(u64, PatchOp<T>)>,
+}
+
+impl<T: Send + Sync + 'static> Default for CurveChangeLog<T> {
+    fn default() -> Self {
+        CurveChangeLog {
+            revision: 0,
+            compacted_revision: 0,
+            ops: vec![],
+        }
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> CurveChangeLog<T> {
+    /// Returns the latest revision of the curve. Every recorded change increases the revision by one
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Records a change to the curve and returns its revision
+    pub fn record(&mut self, op: PatchOp<T>) -> u64 {
+        self.revision += 1;
+        self.ops.push((self.revision, op));
+        self.revision
+    }
+
+    /// Returns a patch containing every change since the given revision, or `None` if the changes have been compacted
+    /// away and the client needs a [`CurvePatch::snapshot`].
+    ///
+    /// Changes that are completely overridden by a later change, such as an insert followed by a truncation over the
+    /// same tick, are left out of the patch.
+    pub fn patch_since(&self, revision: u64) -> Option<CurvePatch<T>> {
+        if revision < self.compacted_revision {
+            return None;
+        }
+        let pending = self
+            .ops
+            .iter()
+            .filter(|(op_revision, _)| *op_revision > revision)
+            .collect::<Vec<&(u64, PatchOp<T>)>>();
+        let ops = pending
+            .iter()
+            .enumerate()
+            .filter(|(index, (_, op))| {
+                !pending[index + 1..]
+                    .iter()
+                    .any(|(_, later)| later.supersedes(op))
+            })
+            .map(|(_, op)| (*op).clone())
+            .collect();
+        Some(CurvePatch {
+            base_revision: revision,
+            revision: self.revision,
+            ops,
+        })
+    }
+
+    /// Drops every change up to and including the given revision, usually the oldest revision acknowledged by all
+    /// clients. Clients older than that need a [`CurvePatch::snapshot`].
+    pub fn compact(&mut self, revision: u64) {
+        let revision = revision.min(self.revision);
+        self.ops.retain(|(op_revision, _)| *op_revision > revision);
+        self.compacted_revision = self.compacted_revision.max(revision);
+    }
+}
+
+/// Extension trait for [`App`] to replicate curves through their [`CurveChangeLog`]
+pub trait ReplicateCurve {
+    /// Registers the curve `C`, see [`RegisterCurve::register_curve`], for replication.
+    ///
+    /// Every entity that the curve is inserted on is given a [`CurveChangeLog`] holding the keyframes it was inserted
+    /// with, so clients that start from an empty curve can be patched from revision 0.
+    fn replicate_curve<C: ComponentCurve>(&mut self) -> &mut Self;
+}
+
+impl ReplicateCurve for App {
+    fn replicate_curve<C: ComponentCurve>(&mut self) -> &mut Self {
+        self.register_curve::<C>();
+        let newly_registered = self
+            .world_mut()
+            .get_resource_or_init::<ReplicationRegistry>()
+            .curves
+            .insert(TypeId::of::<C>());
+        if !newly_registered {
+            return self;
+        }
+        self.add_observer(record_curve_replaced::<C>)
+            .add_observer(record_curve_inserted::<C>)
+    }
+}
+
+/// The curve types registered with [`ReplicateCurve::replicate_curve`]
+#[derive(Resource, Default)]
+struct ReplicationRegistry {
+    curves: HashSet<TypeId>,
+}
+
+/// Records the removal of every keyframe of a curve that is being replaced or removed from its entity
+fn record_curve_replaced<C: ComponentCurve>(
+    trigger: Trigger<OnReplace, C>,
+    mut curves: Query<(&C, &mut CurveChangeLog<C::State>)>,
+) {
+    let Ok((curve, mut change_log)) = curves.get_mut(trigger.entity()) else {
+        return;
+    };
+    if curve.range(..).next().is_some() {
+        change_log.record(PatchOp::Remove {
+            ticks: (Bound::Unbounded, Bound::Unbounded),
+        });
+    }
+}
+
+/// Records every keyframe of a curve inserted onto its entity, adding a [`CurveChangeLog`] if there isn't one yet
+fn record_curve_inserted<C: ComponentCurve>(
+    trigger: Trigger<OnInsert, C>,
+    mut curves: Query<(&C, Option<&mut CurveChangeLog<C::State>>)>,
+    mut commands: Commands,
+) {
+    let Ok((curve, change_log)) = curves.get_mut(trigger.entity()) else {
+        return;
+    };
+    let inserts = curve.range(..).map(|(tick, keyframe)| PatchOp::Insert {
+        tick: *tick,
+        keyframe: keyframe.clone(),
+    });
+    match change_log {
+        Some(mut change_log) => {
+            for op in inserts {
+                change_log.record(op);
+            }
+        }
+        None => {
+            let mut change_log = CurveChangeLog::default();
+            for op in inserts {
+                change_log.record(op);
+            }
+            commands.entity(trigger.entity()).insert(change_log);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ops::Bound, sync::mpsc};
+
+    use bevy::{
+        ecs::system::RunSystemOnce,
+        prelude::{App, Component, Entity},
+    };
+
+    use super::{CurveChangeLog, CurvePatch, PatchOp, ReplicateCurve};
+    use crate::prelude::{CurveMut, CurveTrait, CurvesPlugin, SteppedCurve, SteppedKeyframe};
+
+    #[derive(Component, Clone, Debug, PartialEq)]
+    struct Health(u32);
+
+    impl SteppedKeyframe<Health> for Health {}
+
+    /// Runs `change` against the server curve once and returns the latest patch for a client at `revision`
+    fn step_server(
+        app: &mut App,
+        entity: Entity,
+        revision: u64,
+        change: impl Fn(&mut CurveMut<SteppedCurve<Health>>, Entity) + Send + Sync + 'static,
+    ) -> CurvePatch<Health> {
+        app.world_mut()
+            .run_system_once(move |mut curves: CurveMut<SteppedCurve<Health>>| {
+                change(&mut curves, entity)
+            })
+            .unwrap();
+        app.update();
+        app.world()
+            .get::<CurveChangeLog<Health>>(entity)
+            .unwrap()
+            .patch_since(revision)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_curve_patches() {
+        let mut app = App::new();
+        app.add_plugins(CurvesPlugin);
+        app.replicate_curve::<SteppedCurve<Health>>();
+        let entity = app
+            .world_mut()
+            .spawn((
+                SteppedCurve::<Health>::new(),
+                CurveChangeLog::<Health>::default(),
+            ))
+            .id();
+
+        let (sender, receiver) = mpsc::channel::<CurvePatch<Health>>();
+        let mut client = SteppedCurve::<Health>::new();
+        let mut applied = 0;
+
+        let first = step_server(&mut app, entity, applied, |curves, entity| {
+            curves.insert_keyframe(entity, 0, Health(10));
+            curves.insert_keyframe(entity, 10, Health(5));
+            curves.insert_keyframe(entity, 10, Health(6));
+            curves.insert_keyframe(entity, 20, Health(0));
+        });
+        // The first insert on tick 10 is overridden by the second so it isn't sent
+        assert_eq!(first.ops.len(), 3);
+        sender.send(first.clone()).unwrap();
+        // A duplicated message is ignored
+        sender.send(first.clone()).unwrap();
+        for patch in receiver.try_iter() {
+            assert!(patch.apply(&mut client, &mut applied));
+        }
+        assert_eq!(applied, 4);
+        assert_eq!(client.get_state(15), Some(Health(6)));
+
+        let second = step_server(&mut app, entity, applied, |curves, entity| {
+            curves.insert_keyframe(entity, 30, Health(1));
+            curves.remove_future_keyframes(entity, 15);
+            curves.remove_keyframe(entity, 0);
+        });
+        // The insert on tick 30 is overridden by the truncation after it so only the removals are sent
+        assert_eq!(
+            second.ops,
+            vec![
+                (
+                    6,
+                    PatchOp::Remove {
+                        ticks: (Bound::Included(15), Bound::Unbounded)
+                    }
+                ),
+                (
+                    7,
+                    PatchOp::Remove {
+                        ticks: (Bound::Included(0), Bound::Included(0))
+                    }
+                ),
+            ]
+        );
+        // Patches arriving out of order are still applied correctly
+        sender.send(second.clone()).unwrap();
+        sender.send(first).unwrap();
+        for patch in receiver.try_iter() {
+            assert!(patch.apply(&mut client, &mut applied));
+        }
+        let server = app.world().get::<SteppedCurve<Health>>(entity).unwrap();
+        assert_eq!(
+            client.range(..).collect::<Vec<_>>(),
+            server.range(..).collect::<Vec<_>>()
+        );
+
+        // A client that missed the first patch can't apply the second and needs a snapshot instead
+        let mut late_client = SteppedCurve::<Health>::new();
+        late_client.insert_keyframe(50, Health(99));
+        let mut late_applied = 0;
+        assert!(!second.apply(&mut late_client, &mut late_applied));
+        let mut log = app
+            .world_mut()
+            .get_mut::<CurveChangeLog<Health>>(entity)
+            .unwrap();
+        log.compact(applied);
+        assert!(log.patch_since(0).is_none());
+        let revision = log.revision();
+        let server = app.world().get::<SteppedCurve<Health>>(entity).unwrap();
+        let snapshot = CurvePatch::snapshot(server, revision);
+        assert!(snapshot.apply(&mut late_client, &mut late_applied));
+        assert_eq!(late_applied, applied);
+        assert_eq!(
+            late_client.range(..).collect::<Vec<_>>(),
+            vec![(&10, &Health(6))]
+        );
+    }
+
+    #[test]
+    fn test_remove_then_insert() {
+        let mut app = App::new();
+        app.add_plugins(CurvesPlugin);
+        app.replicate_curve::<SteppedCurve<Health>>();
+        let entity = app
+            .world_mut()
+            .spawn((
+                SteppedCurve::<Health>::new(),
+                CurveChangeLog::<Health>::default(),
+            ))
+            .id();
+
+        let mut client = SteppedCurve::<Health>::new();
+        let mut applied = 0;
+        let first = step_server(&mut app, entity, applied, |curves, entity| {
+            curves.insert_keyframe(entity, 0, Health(10));
+            curves.insert_keyframe(entity, 10, Health(5));
+        });
+        assert!(first.apply(&mut client, &mut applied));
+        let second = step_server(&mut app, entity, applied, |curves, entity| {
+            curves.remove_future_keyframes(entity, 5);
+            curves.insert_keyframe(entity, 10, Health(7));
+            curves.insert_keyframe(entity, 20, Health(1));
+            curves.insert_keyframe(entity, 20, Health(2));
+        });
+        assert!(second.apply(&mut client, &mut applied));
+
+        let server = app.world().get::<SteppedCurve<Health>>(entity).unwrap();
+        assert_eq!(
+            client.range(..).collect::<Vec<_>>(),
+            vec![(&0, &Health(10)), (&10, &Health(7)), (&20, &Health(2))]
+        );
+        assert_eq!(
+            client.range(..).collect::<Vec<_>>(),
+            server.range(..).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_curve_inserted() {
+        let mut app = App::new();
+        app.add_plugins(CurvesPlugin);
+        app.replicate_curve::<SteppedCurve<Health>>();
+
+        let mut curve = SteppedCurve::<Health>::new();
+        curve.insert_keyframe(1, Health(10));
+        curve.insert_keyframe(5, Health(20));
+        let entity = app.world_mut().spawn(curve).id();
+        app.update();
+
+        // The log is added for the spawned curve and holds the keyframes it was spawned with
+        let mut client = SteppedCurve::<Health>::new();
+        let mut applied_revision = 0;
+        let patch = app
+            .world()
+            .get::<CurveChangeLog<Health>>(entity)
+            .unwrap()
+            .patch_since(applied_revision)
+            .unwrap();
+        assert!(patch.apply(&mut client, &mut applied_revision));
+        assert_eq!(client.get_state(3), Some(Health(10)));
+        assert_eq!(client.get_state(6), Some(Health(20)));
+
+        // Replacing the curve, like an asset reload does, removes the old keyframes before inserting the new ones
+        let mut reloaded = SteppedCurve::<Health>::new();
+        reloaded.insert_keyframe(2, Health(30));
+        app.world_mut().entity_mut(entity).insert(reloaded);
+        let patch = app
+            .world()
+            .get::<CurveChangeLog<Health>>(entity)
+            .unwrap()
+            .patch_since(applied_revision)
+            .unwrap();
+        assert!(patch.apply(&mut client, &mut applied_revision));
+        assert_eq!(client.get_state(1), None);
+        assert_eq!(client.get_state(6), Some(Health(30)));
+    }
+
+    #[test]
+    fn test_malformed_patch() {
+        let mut client = SteppedCurve::<Health>::new();
+        client.insert_keyframe(5, Health(1));
+        let mut applied = 0;
+        for ticks in [
+            (Bound::Excluded(5), Bound::Excluded(5)),
+            (Bound::Included(10), Bound::Included(5)),
+        ] {
+            let patch = CurvePatch {
+                base_revision: 0,
+                revision: 2,
+                ops: vec![
+                    (
+                        1,
+                        PatchOp::Insert {
+                            tick: 0,
+                            keyframe: Health(2),
+                        },
+                    ),
+                    (2, PatchOp::Remove { ticks }),
+                ],
+            };
+            assert!(!patch.apply(&mut client, &mut applied));
+        }
+        assert_eq!(applied, 0);
+        assert_eq!(client.range(..).collect::<Vec<_>>(), vec![(&5, &Health(1))]);
+    }
+}
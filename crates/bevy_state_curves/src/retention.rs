@@ -0,0 +1,175 @@
+use bevy::{
+    app::{App, Update},
+    prelude::{
+        Component, Entity, IntoSystemConfigs, IntoSystemSetConfigs, Query, Res, Resource, With,
+    },
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(all(feature = "type_path", not(feature = "reflect")))]
+use bevy::reflect::TypePath;
+
+#[cfg(feature = "reflect")]
+use bevy::{
+    ecs::reflect::{ReflectComponent, ReflectResource},
+    reflect::Reflect,
+};
+
+use crate::{
+    curves::CurveTrait,
+    events::CurveMut,
+    plugin::{ComponentCurve, CurveSystems, RegisterCurve, ViewedTick},
+    timeline::Timeline,
+    GameTick,
+};
+
+/// How much history curves registered with [`RetainCurve::retain_curve`] keep. Old keyframes are pruned
+/// automatically.
+///
+/// ## Explanation:
+///
+/// - Inserted as a resource it is the policy for every retained curve. Defaults to [`CurveRetention::KeepAll`].
+/// - Inserted as a component it overrides the resource for every curve on that entity.
+/// - The window is measured back from the [`Timeline`]s simulation tick, or the [`ViewedTick`] if there is no
+///   [`Timeline`].
+/// - The last keyframe before the window is always kept so the state at the start of the window stays the same. See
+///   [`CurveTrait::remove_keyframes_before`].
+/// - Keyframes are removed through [`CurveMut`] so a [`KeyframesRemoved`](crate::events::KeyframesRemoved) event is
+///   sent for them.
+/// - Pruning frees memory rather than rewriting history, so keyframes before a curve's
+///   [frozen tick](crate::curves::CurveTrait::freeze_history) are removed as well.
+///
+/// ```rust,ignore
+/// app.retain_curve::<LinearCurve<BodyAngle>>();
+/// app.insert_resource(CurveRetention::KeepTicks(600));
+/// commands.spawn((LinearCurve::<BodyAngle>::new(), CurveRetention::KeepKeyframes(10)));
+/// ```
+#[derive(Resource, Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(all(feature = "type_path", not(feature = "reflect")), derive(TypePath))]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component, Resource))]
+pub enum CurveRetention {
+    /// Never remove any keyframes
+    #[default]
+    KeepAll,
+    /// Keep the keyframes within the given number of ticks before the simulation tick
+    KeepTicks(GameTick),
+    /// Keep the given number of most recent keyframes. At least one keyframe is always kept
+    KeepKeyframes(usize),
+}
+
+impl CurveRetention {
+    /// Returns the tick that keyframes before are pruned from the curve, or `None` if nothing needs to be pruned
+    pub fn prune_tick<T>(&self, curve: &impl CurveTrait<T>, head: GameTick) -> Option<GameTick> {
+        match self {
+            CurveRetention::KeepAll => None,
+            CurveRetention::KeepTicks(ticks) => Some(head.saturating_sub(*ticks)),
+            CurveRetention::KeepKeyframes(keyframes) => curve
+                .range(..)
+                .nth_back(keyframes.saturating_sub(1))
+                .map(|(tick, _)| *tick),
+        }
+    }
+}
+
+/// Prunes every curve of type `C` according to its [`CurveRetention`]
+fn enforce_curve_retention<C: ComponentCurve>(
+    retention: Res<CurveRetention>,
+    viewed_tick: Res<ViewedTick>,
+    timeline: Option<Res<Timeline>>,
+    entities: Query<(Entity, Option<&CurveRetention>), With<C>>,
+    mut curves: CurveMut<C>,
+) {
+    let head = timeline.map_or(viewed_tick.0, |timeline| timeline.simulation_tick());
+    for (entity, entity_retention) in entities.iter() {
+        let Some(tick) = curves.get(entity).and_then(|curve| {
+            entity_retention
+                .unwrap_or(&retention)
+                .prune_tick(curve, head)
+        }) else {
+            continue;
+        };
+        curves.remove_keyframes_before(entity, tick);
+    }
+}
+
+/// Extension trait for [`App`] to prune old keyframes from curves according to their [`CurveRetention`]
+pub trait RetainCurve {
+    /// Registers the curve `C`, see [`RegisterCurve::register_curve`], and prunes its old keyframes according to its
+    /// [`CurveRetention`] every [`Update`] after its state is sampled.
+    ///
+    /// This also adds the [`CurveRetention`] resource, keeping every keyframe, if it hasn't been inserted yet.
+    fn retain_curve<C: ComponentCurve>(&mut self) -> &mut Self;
+}
+
+impl RetainCurve for App {
+    fn retain_curve<C: ComponentCurve>(&mut self) -> &mut Self {
+        self.register_curve::<C>();
+        self.init_resource::<CurveRetention>();
+        self.configure_sets(
+            Update,
+            CurveSystems::EnforceRetention.after(CurveSystems::SampleState),
+        );
+        self.add_systems(
+            Update,
+            enforce_curve_retention::<C>.in_set(CurveSystems::EnforceRetention),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{App, Component};
+
+    use super::{CurveRetention, RetainCurve};
+    use crate::prelude::{CurveTrait, CurvesPlugin, SteppedCurve, SteppedKeyframe, ViewedTick};
+
+    #[derive(Component, Clone, Debug, PartialEq)]
+    struct Health(u32);
+
+    impl SteppedKeyframe<Health> for Health {}
+
+    #[test]
+    fn test_curve_retention() {
+        let mut app = App::new();
+        app.add_plugins(CurvesPlugin);
+        app.retain_curve::<SteppedCurve<Health>>();
+        app.insert_resource(CurveRetention::KeepTicks(10));
+
+        let mut curve = SteppedCurve::new();
+        for tick in [0, 5, 12, 18, 25] {
+            curve.insert_keyframe(tick, Health(tick as u32));
+        }
+        let global = app.world_mut().spawn(curve.clone()).id();
+        let keyframes = app
+            .world_mut()
+            .spawn((curve.clone(), CurveRetention::KeepKeyframes(3)))
+            .id();
+        let keep_all = app.world_mut().spawn((curve, CurveRetention::KeepAll)).id();
+
+        app.world_mut().resource_mut::<ViewedTick>().0 = 30;
+        app.update();
+
+        let ticks = |app: &App, entity| {
+            app.world()
+                .get::<SteppedCurve<Health>>(entity)
+                .unwrap()
+                .range(..)
+                .map(|(tick, _)| *tick)
+                .collect::<Vec<u64>>()
+        };
+        // The keyframe on tick 18 is kept since the state on tick 20, the start of the window, comes from it
+        assert_eq!(ticks(&app, global), vec![18, 25]);
+        assert_eq!(
+            app.world()
+                .get::<SteppedCurve<Health>>(global)
+                .unwrap()
+                .get_state(20),
+            Some(Health(18))
+        );
+        assert_eq!(ticks(&app, keyframes), vec![12, 18, 25]);
+        assert_eq!(ticks(&app, keep_all), vec![0, 5, 12, 18, 25]);
+    }
+}
@@ -0,0 +1,223 @@
+use std::{any::TypeId, ops::Bound};
+
+use bevy::{
+    ecs::world::Command,
+    prelude::{Commands, Entity, Resource, World},
+};
+
+use crate::{
+    events::{send_keyframe_inserted, send_keyframes_removed},
+    lifetime::{rewind_lifetimes, StateLifetime},
+    plugin::{ComponentCurve, ViewedTick},
+    timeline::Timeline,
+    GameTick,
+};
+
+/// Rewinds every curve of a single type, see [`rewind_curves`]
+type RewindFn = fn(&mut World, GameTick);
+
+/// Every curve type registered with [`RegisterCurve::register_curve`](crate::plugin::RegisterCurve::register_curve).
+///
+/// Used by [`RewindTo`] to rewind every curve backed entity in the world without knowing the curve types.
+#[derive(Resource, Default)]
+pub struct CurveRegistry {
+    curves: Vec<(TypeId, RewindFn)>,
+}
+
+impl CurveRegistry {
+    /// Adds the curve `C` to the registry. Returns false if the curve was already registered
+    pub(crate) fn register<C: ComponentCurve>(&mut self) -> bool {
+        let type_id = TypeId::of::<C>();
+        if self.curves.iter().any(|(id, _)| *id == type_id) {
+            return false;
+        }
+        self.curves.push((type_id, rewind_curves::<C>));
+        true
+    }
+
+    /// Returns how many curve types are registered
+    pub fn len(&self) -> usize {
+        self.curves.len()
+    }
+
+    /// Returns true if no curve types are registered
+    pub fn is_empty(&self) -> bool {
+        self.curves.is_empty()
+    }
+}
+
+/// A [`Command`] that rewinds the world to the given tick so the simulation can branch off from it.
+///
+/// ## Explanation:
+///
+/// For every curve type in the [`CurveRegistry`]:
+///
+/// - The curve is sampled at the tick and the state is written into the entity, or removed if there is no state.
+/// - Every keyframe after the tick is removed. The keyframe on the tick itself is kept.
+/// - If the tick had state but no keyframe then the sampled state is inserted as a keyframe on the tick, so that
+///   interpolated state isn't lost when the keyframes after it are removed.
+/// - A [`KeyframesRemoved`](crate::events::KeyframesRemoved) and [`KeyframeInserted`](crate::events::KeyframeInserted)
+///   event is sent for these changes.
+/// - Rewinding ignores the curves' [frozen tick](crate::curves::CurveTrait::freeze_history) and discards frozen
+///   keyframes after the tick too. Move the frozen tick back with the rewind if history should stay frozen.
+///
+/// Entities with a [`StateLifetime`] that were spawned after the tick are despawned and despawns after the tick are
+/// cleared.
+///
+/// Afterwards the [`ViewedTick`] is set to the tick and, if there is a [`Timeline`], its simulation head and viewed
+/// tick are moved to the tick so that simulation continues forward from there.
+///
+/// ```rust,ignore
+/// commands.rewind_to(120);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RewindTo(pub GameTick);
+
+impl Command for RewindTo {
+    fn apply(self, world: &mut World) {
+        let rewinds = world
+            .get_resource::<CurveRegistry>()
+            .map(|registry| {
+                registry
+                    .curves
+                    .iter()
+                    .map(|(_, rewind)| *rewind)
+                    .collect::<Vec<RewindFn>>()
+            })
+            .unwrap_or_default();
+        for rewind in rewinds {
+            rewind(world, self.0);
+        }
+        rewind_lifetimes(world, self.0);
+
+        world.insert_resource(ViewedTick(self.0));
+        if let Some(mut timeline) = world.get_resource_mut::<Timeline>() {
+            timeline.set_simulation_tick(self.0);
+            timeline.scrub_to(self.0);
+        }
+    }
+}
+
+/// Extension trait for [`Commands`] to rewind the world
+pub trait RewindCommands {
+    /// Queues a [`RewindTo`] command that rewinds every registered curve to the given tick
+    fn rewind_to(&mut self, tick: GameTick);
+}
+
+impl RewindCommands for Commands<'_, '_> {
+    fn rewind_to(&mut self, tick: GameTick) {
+        self.queue(RewindTo(tick));
+    }
+}
+
+/// Removes every keyframe of `C` after the tick and writes the state at the tick into the entity. See [`RewindTo`]
+fn rewind_curves<C: ComponentCurve>(world: &mut World, tick: GameTick) {
+    let mut curves = world.query::<(Entity, &mut C, Option<&StateLifetime>)>();
+    let states = curves
+        .iter_mut(world)
+        .map(|(entity, mut curve, lifetime)| {
+            let state = match lifetime {
+                Some(lifetime) => lifetime.get_state(&*curve, tick),
+                None => curve.get_state(tick),
+            };
+            let removed = curve
+                .range((Bound::Excluded(tick), Bound::Unbounded))
+                .next()
+                .is_some();
+            curve.remove_future_keyframes(&tick.saturating_add(1));
+            let mut inserted = false;
+            let mut evicted = None;
+            if let Some(state) = state.as_ref() {
+                if curve.get_keyframe(tick).is_none() {
+                    evicted = curve
+                        .insert_keyframe(tick, state.clone())
+                        .map(|(tick, _)| tick);
+                    inserted = evicted != Some(tick);
+                }
+            }
+            (entity, state, removed, inserted, evicted)
+        })
+        .collect::<Vec<(Entity, Option<C::State>, bool, bool, Option<GameTick>)>>();
+
+    for (entity, state, removed, inserted, evicted) in states {
+        if removed {
+            send_keyframes_removed::<C::State>(
+                world,
+                entity,
+                (Bound::Excluded(tick), Bound::Unbounded),
+            );
+        }
+        if let (true, Some(state)) = (inserted, state.as_ref()) {
+            send_keyframe_inserted(world, entity, tick, state.clone());
+            // The storage had to drop its oldest keyframe to make room for the state on the tick
+            if let Some(evicted) = evicted {
+                send_keyframes_removed::<C::State>(
+                    world,
+                    entity,
+                    (Bound::Included(evicted), Bound::Included(evicted)),
+                );
+            }
+        }
+        let mut entity = world.entity_mut(entity);
+        match state {
+            Some(state) => {
+                entity.insert(state);
+            }
+            None => {
+                entity.remove::<C::State>();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::world::Command,
+        prelude::{App, Component},
+    };
+
+    use super::{CurveRegistry, RewindTo};
+    use crate::prelude::{
+        CurveTrait, CurvesPlugin, LinearCurve, LinearKeyframe, RegisterCurve, Timeline, ViewedTick,
+    };
+
+    #[derive(Component, Clone, Debug, PartialEq)]
+    struct Radius(f32);
+
+    impl LinearKeyframe<Radius> for Radius {
+        fn lerp(&self, next_frame_state: &Radius, ratio: f64) -> Radius {
+            Radius(self.0 + (next_frame_state.0 - self.0) * ratio as f32)
+        }
+    }
+
+    #[test]
+    fn test_rewind_to() {
+        let mut app = App::new();
+        app.add_plugins(CurvesPlugin);
+        app.register_curve::<LinearCurve<Radius>>();
+        app.register_curve::<LinearCurve<Radius>>();
+        assert_eq!(app.world().resource::<CurveRegistry>().len(), 1);
+        app.init_resource::<Timeline>();
+        app.world_mut()
+            .resource_mut::<Timeline>()
+            .set_simulation_tick(20);
+
+        let mut curve = LinearCurve::new();
+        curve.insert_keyframe(0, Radius(0.0));
+        curve.insert_keyframe(10, Radius(1.0));
+        curve.insert_keyframe(20, Radius(2.0));
+        let entity = app.world_mut().spawn(curve).id();
+
+        RewindTo(15).apply(app.world_mut());
+
+        let world = app.world();
+        assert_eq!(world.get::<Radius>(entity), Some(&Radius(1.5)));
+        let curve = world.get::<LinearCurve<Radius>>(entity).unwrap();
+        assert!(curve.get_keyframe(20).is_none());
+        assert_eq!(curve.get_keyframe(15), Some(&Radius(1.5)));
+        assert_eq!(curve.get_state(20), Some(Radius(1.5)));
+        assert_eq!(world.resource::<ViewedTick>().0, 15);
+        assert_eq!(world.resource::<Timeline>().simulation_tick(), 15);
+    }
+}
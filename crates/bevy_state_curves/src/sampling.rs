@@ -0,0 +1,365 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    app::{App, Update},
+    prelude::{DetectChanges, Entity, IntoSystemConfigs, Query, Ref, Res, ResMut, Resource},
+    tasks::{ComputeTaskPool, ParallelSliceMut, TaskPool},
+};
+
+use crate::{
+    curves::{Curve, LinearCurve, SteppedCurve},
+    keyframe_trait::{LinearKeyframe, SteppedKeyframe},
+    lifetime::StateLifetime,
+    plugin::{ComponentCurve, CurveSystems, RegisterCurve, ViewedTick},
+    storage::CurveStorage,
+    tick::Tick,
+    GameTick,
+};
+
+/// How many curves each task samples when sampling in parallel. Batches smaller than this are sampled on the
+/// calling thread
+const BATCH_CHUNK_SIZE: usize = 1024;
+
+/// Remembers the keyframes on either side of the last sampled tick of a single curve so that sampling nearby ticks,
+/// such as during playback, doesn't need to look up the keyframes again.
+///
+/// ## Explanation:
+///
+/// - Sample with [`LinearCurve::get_state_with_cursor`] or [`SteppedCurve::get_state_with_cursor`], which give the
+///   same state as `get_state`.
+/// - While the sampled tick stays between the cached keyframes the state is computed straight from the cursor.
+/// - The cursor is refreshed whenever the tick leaves the cached keyframes, the curve's
+///   [`revision`](crate::curves::Curve::revision) changed or it is used with a different curve, so it never returns
+///   stale state.
+/// - Keep one cursor per curve, since switching curves refreshes the cursor every time.
+#[derive(Debug, Clone)]
+pub struct CurveCursor<T, K: Tick = GameTick> {
+    /// The id and revision of the curve that the keyframes were cached from
+    revision: Option<(u64, u64)>,
+    prev: Option<(K, T)>,
+    next: Option<(K, T)>,
+}
+
+impl<T, K: Tick> Default for CurveCursor<T, K> {
+    fn default() -> Self {
+        CurveCursor {
+            revision: None,
+            prev: None,
+            next: None,
+        }
+    }
+}
+
+impl<T: Clone, K: Tick> CurveCursor<T, K> {
+    /// Creates a new empty [`CurveCursor`]. It is filled on the first sample
+    pub fn new() -> CurveCursor<T, K> {
+        CurveCursor::default()
+    }
+
+    /// Returns true if the cached keyframes are still the keyframes on either side of the tick
+    fn contains(&self, tick: K, revision: (u64, u64)) -> bool {
+        self.revision == Some(revision)
+            && self
+                .prev
+                .as_ref()
+                .is_none_or(|(prev_tick, _)| *prev_tick <= tick)
+            && self
+                .next
+                .as_ref()
+                .is_none_or(|(next_tick, _)| tick < *next_tick)
+    }
+
+    /// Caches the keyframe on or before the tick and the keyframe after it, unless they are already cached
+    fn seek<S: CurveStorage<K, T>>(&mut self, curve: &Curve<T, K, S>, tick: K) {
+        let revision = (curve.id(), curve.revision());
+        if self.contains(tick, revision) {
+            return;
+        }
+        self.revision = Some(revision);
+        self.prev = curve
+            .prev_keyframe(tick)
+            .map(|(tick, keyframe)| (*tick, keyframe.clone()));
+        self.next = curve
+            .keyframe_after(tick)
+            .map(|(tick, keyframe)| (*tick, keyframe.clone()));
+    }
+}
+
+impl<T: LinearKeyframe<T>, K: Tick, S: CurveStorage<K, T>> LinearCurve<T, K, S> {
+    /// Returns the same state as [`CurveTrait::get_state`](crate::curves::CurveTrait::get_state) while caching the
+    /// keyframes around the tick in the cursor. See [`CurveCursor`]
+    pub fn get_state_with_cursor(&self, tick: K, cursor: &mut CurveCursor<T, K>) -> Option<T> {
+        cursor.seek(&self.curve, tick);
+        let (prev_tick, prev) = cursor.prev.as_ref()?;
+        let next = cursor.next.as_ref().map(|(tick, next)| (tick, next));
+        LinearCurve::<T, K, S>::interpolate((prev_tick, prev), next, tick, 0.0).ok()
+    }
+}
+
+impl<T: SteppedKeyframe<T>, K: Tick, S: CurveStorage<K, T>> SteppedCurve<T, K, S> {
+    /// Returns the same state as [`CurveTrait::get_state`](crate::curves::CurveTrait::get_state) while caching the
+    /// keyframes around the tick in the cursor. See [`CurveCursor`]
+    pub fn get_state_with_cursor(&self, tick: K, cursor: &mut CurveCursor<T, K>) -> Option<T> {
+        cursor.seek(&self.curve, tick);
+        cursor.prev.as_ref().map(|(_, prev)| prev.clone())
+    }
+}
+
+/// The state of every curve of type `C` sampled at the same tick, stored as contiguous arrays.
+///
+/// ## Explanation:
+///
+/// - Added and kept up to date by [`BatchSampleCurve::batch_sample_curve`]. It is resampled at the [`ViewedTick`]
+///   whenever the tick, any of the curves, or the set of entities changes.
+/// - `entities()[i]` is the entity that `states()[i]` was sampled from. States are `None` where the curve has no
+///   state, including outside of the entities [`StateLifetime`].
+/// - Large batches are split across the [`ComputeTaskPool`].
+#[derive(Resource, Debug)]
+pub struct CurveSamples<C: ComponentCurve> {
+    tick: GameTick,
+    entities: Vec<Entity>,
+    states: Vec<Option<C::State>>,
+    marker: PhantomData<fn() -> C>,
+}
+
+impl<C: ComponentCurve> Default for CurveSamples<C> {
+    fn default() -> Self {
+        CurveSamples {
+            tick: 0,
+            entities: vec![],
+            states: vec![],
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<C: ComponentCurve> CurveSamples<C> {
+    /// Returns the tick that the curves were sampled at
+    pub fn tick(&self) -> GameTick {
+        self.tick
+    }
+
+    /// Returns the entity of every sampled curve
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    /// Returns the state of every sampled curve, in the same order as [`CurveSamples::entities`]
+    pub fn states(&self) -> &[Option<C::State>] {
+        &self.states
+    }
+
+    /// Returns an iterator over every entity and its sampled state
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (Entity, Option<&C::State>)> {
+        self.entities
+            .iter()
+            .zip(self.states.iter())
+            .map(|(entity, state)| (*entity, state.as_ref()))
+    }
+
+    /// Samples every curve at the tick, replacing the previous samples. Batches larger than a single chunk are sampled
+    /// in parallel on the given task pool.
+    pub fn sample<'a>(
+        &mut self,
+        curves: impl IntoIterator<Item = (Entity, &'a C, Option<&'a StateLifetime>)>,
+        tick: GameTick,
+        task_pool: &TaskPool,
+    ) {
+        let (entities, curves): (Vec<Entity>, Vec<(&C, Option<&StateLifetime>)>) = curves
+            .into_iter()
+            .map(|(entity, curve, lifetime)| (entity, (curve, lifetime)))
+            .unzip();
+        self.tick = tick;
+        self.entities = entities;
+        self.states.clear();
+        self.states.resize_with(curves.len(), || None);
+
+        let sample_chunk = |chunk_index: usize, states: &mut [Option<C::State>]| {
+            let offset = chunk_index * BATCH_CHUNK_SIZE;
+            for (state, (curve, lifetime)) in states.iter_mut().zip(&curves[offset..]) {
+                *state = match lifetime {
+                    Some(lifetime) => lifetime.get_state(*curve, tick),
+                    None => curve.get_state(tick),
+                };
+            }
+        };
+        if curves.len() <= BATCH_CHUNK_SIZE {
+            sample_chunk(0, &mut self.states);
+        } else {
+            self.states
+                .par_chunk_map_mut(task_pool, BATCH_CHUNK_SIZE, sample_chunk);
+        }
+    }
+}
+
+/// Extension trait for [`App`] to sample every curve of a type into a [`CurveSamples`] resource
+pub trait BatchSampleCurve {
+    /// Registers the curve `C`, see [`RegisterCurve::register_curve`], and keeps a [`CurveSamples<C>`] resource
+    /// sampled at the [`ViewedTick`]. Sampling runs in [`CurveSystems::SampleState`].
+    fn batch_sample_curve<C: ComponentCurve>(&mut self) -> &mut Self;
+}
+
+impl BatchSampleCurve for App {
+    fn batch_sample_curve<C: ComponentCurve>(&mut self) -> &mut Self {
+        self.register_curve::<C>();
+        if self.world().contains_resource::<CurveSamples<C>>() {
+            return self;
+        }
+        self.init_resource::<CurveSamples<C>>();
+        self.add_systems(
+            Update,
+            sample_curve_batch::<C>.in_set(CurveSystems::SampleState),
+        )
+    }
+}
+
+/// Resamples the [`CurveSamples`] of `C` if the [`ViewedTick`], any curve, or the set of curves changed
+fn sample_curve_batch<C: ComponentCurve>(
+    viewed_tick: Res<ViewedTick>,
+    curves: Query<(Entity, Ref<C>, Option<Ref<StateLifetime>>)>,
+    mut samples: ResMut<CurveSamples<C>>,
+) {
+    let changed = viewed_tick.is_changed()
+        || samples.tick != viewed_tick.0
+        || samples.entities.len() != curves.iter().len()
+        || curves.iter().any(|(_, curve, lifetime)| {
+            curve.is_changed() || lifetime.is_some_and(|lifetime| lifetime.is_changed())
+        });
+    if !changed {
+        return;
+    }
+    let task_pool = ComputeTaskPool::get_or_init(TaskPool::default);
+    samples.sample(
+        curves.iter().map(|(entity, curve, lifetime)| {
+            (entity, curve.into_inner(), lifetime.map(Ref::into_inner))
+        }),
+        viewed_tick.0,
+        task_pool,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        prelude::{App, Component, Entity},
+        tasks::TaskPool,
+    };
+
+    use super::{BatchSampleCurve, CurveCursor, CurveSamples};
+    use crate::prelude::{
+        CurveTrait, CurvesPlugin, LinearCurve, LinearKeyframe, StateLifetime, SteppedCurve,
+        ViewedTick,
+    };
+
+    #[derive(Component, Clone, Debug, PartialEq)]
+    struct Radius(f32);
+
+    impl LinearKeyframe<Radius> for Radius {
+        fn lerp(&self, next_frame_state: &Radius, ratio: f64) -> Radius {
+            Radius(self.0 + (next_frame_state.0 - self.0) * ratio as f32)
+        }
+    }
+
+    #[test]
+    fn test_curve_cursor() {
+        let mut curve = LinearCurve::new();
+        curve.insert_keyframe(5, Radius(0.0));
+        curve.insert_keyframe(10, Radius(1.0));
+        curve.insert_keyframe(20, Radius(3.0));
+        let mut cursor = CurveCursor::new();
+        for tick in (0..30).chain((0..30).rev()) {
+            assert_eq!(
+                curve.get_state_with_cursor(tick, &mut cursor),
+                curve.get_state(tick)
+            );
+        }
+
+        // Mutating the curve invalidates the cursor even if the tick stays between the same keyframes
+        assert_eq!(
+            curve.get_state_with_cursor(15, &mut cursor),
+            Some(Radius(2.0))
+        );
+        curve.get_keyframe_mut(20).unwrap().0 = 5.0;
+        assert_eq!(
+            curve.get_state_with_cursor(15, &mut cursor),
+            Some(Radius(3.0))
+        );
+
+        // A cursor used with another curve at the same revision is refreshed rather than reusing the cached keyframes
+        let mut other = LinearCurve::new();
+        other.insert_keyframe(0, Radius(0.0));
+        other.insert_keyframe(10, Radius(10.0));
+        other.insert_keyframe(20, Radius(20.0));
+        other.get_keyframe_mut(20).unwrap().0 = 30.0;
+        assert_eq!(other.curve.revision(), curve.curve.revision());
+        assert_eq!(
+            other.get_state_with_cursor(15, &mut cursor),
+            Some(Radius(20.0))
+        );
+
+        let mut stepped = SteppedCurve::new();
+        stepped.insert_keyframe(5, 1u32);
+        stepped.insert_keyframe(10, 2);
+        let mut cursor = CurveCursor::new();
+        for tick in 0..15 {
+            assert_eq!(
+                stepped.get_state_with_cursor(tick, &mut cursor),
+                stepped.get_state(tick)
+            );
+        }
+    }
+
+    #[test]
+    fn test_batch_sampling() {
+        let mut app = App::new();
+        app.add_plugins(CurvesPlugin);
+        app.batch_sample_curve::<LinearCurve<Radius>>();
+
+        let count = 3000;
+        let entities = (0..count)
+            .map(|index| {
+                let mut curve = LinearCurve::new();
+                curve.insert_keyframe(0, Radius(0.0));
+                curve.insert_keyframe(10, Radius(index as f32));
+                let mut entity = app.world_mut().spawn(curve);
+                if index == 0 {
+                    entity.insert(StateLifetime::new(20));
+                }
+                entity.id()
+            })
+            .collect::<Vec<_>>();
+
+        app.world_mut().resource_mut::<ViewedTick>().0 = 5;
+        app.update();
+
+        let samples = app.world().resource::<CurveSamples<LinearCurve<Radius>>>();
+        assert_eq!(samples.tick(), 5);
+        assert_eq!(samples.states().len(), count);
+        for (entity, state) in samples.iter() {
+            let index = entities.iter().position(|other| *other == entity).unwrap();
+            match index {
+                0 => assert_eq!(state, None),
+                _ => assert_eq!(state, Some(&Radius(index as f32 * 0.5))),
+            }
+        }
+
+        // Sampling directly gives the same result on a single threaded pool
+        let mut direct = CurveSamples::<LinearCurve<Radius>>::default();
+        let mut query = app
+            .world_mut()
+            .query::<(Entity, &LinearCurve<Radius>, Option<&StateLifetime>)>();
+        direct.sample(query.iter(app.world()), 5, &TaskPool::new());
+        let sorted = |samples: &CurveSamples<LinearCurve<Radius>>| {
+            let mut samples = samples
+                .iter()
+                .map(|(entity, state)| (entity, state.cloned()))
+                .collect::<Vec<_>>();
+            samples.sort_by_key(|(entity, _)| *entity);
+            samples
+        };
+        assert_eq!(
+            sorted(&direct),
+            sorted(app.world().resource::<CurveSamples<LinearCurve<Radius>>>())
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use bevy::ecs::entity::{Entity, VisitEntities, VisitEntitiesMut};
+
+use crate::{
+    curves::{CubicCurve, CurveTrait, LinearCurve, PhysicsCurve, PulseCurve, SteppedCurve},
+    keyframe_trait::{
+        CubicKeyframe, LinearKeyframe, PhysicsKeyframe, PulseKeyframe, SteppedKeyframe,
+    },
+    storage::CurveStorage,
+    tick::Tick,
+};
+
+// Curves visit the entities in every one of their keyframes. Through bevy's blanket implementation this also
+// implements `MapEntities`, so curves whose keyframes reference entities are remapped when they are written to a
+// world from a `DynamicScene`.
+macro_rules! impl_visit_entities {
+    ($($curve:ident: $keyframe:ident),*) => {
+        $(
+            impl<T: $keyframe<T> + VisitEntities, K: Tick, S: CurveStorage<K, T>> VisitEntities
+                for $curve<T, K, S>
+            {
+                fn visit_entities<F: FnMut(Entity)>(&self, mut f: F) {
+                    for (_, keyframe) in self.range(..) {
+                        keyframe.visit_entities(&mut f);
+                    }
+                }
+            }
+
+            impl<T: $keyframe<T> + VisitEntitiesMut, K: Tick, S: CurveStorage<K, T>> VisitEntitiesMut
+                for $curve<T, K, S>
+            {
+                fn visit_entities_mut<F: FnMut(&mut Entity)>(&mut self, mut f: F) {
+                    for (_, keyframe) in self.curve.range_mut(..) {
+                        keyframe.visit_entities_mut(&mut f);
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_visit_entities!(
+    LinearCurve: LinearKeyframe,
+    SteppedCurve: SteppedKeyframe,
+    PulseCurve: PulseKeyframe,
+    CubicCurve: CubicKeyframe,
+    PhysicsCurve: PhysicsKeyframe
+);
+
+#[cfg(all(test, feature = "reflect"))]
+mod tests {
+    use bevy::{
+        ecs::{entity::EntityHashMap, reflect::ReflectMapEntities},
+        prelude::{AppTypeRegistry, Entity, World},
+        scene::{ron, serde::SceneDeserializer, DynamicScene},
+    };
+    use serde::de::DeserializeSeed;
+
+    use crate::prelude::{CurveTrait, LinearCurve, StateLifetime, SteppedCurve};
+
+    #[test]
+    fn test_scene_round_trip() {
+        let registry = AppTypeRegistry::default();
+        {
+            let mut registry = registry.write();
+            // Registered by bevy's `App`, but not by a bare `TypeRegistry`
+            registry.register::<Entity>();
+            registry.register::<LinearCurve<f32>>();
+            registry.register::<SteppedCurve<Entity>>();
+            registry.register::<StateLifetime>();
+            registry.register_type_data::<SteppedCurve<Entity>, ReflectMapEntities>();
+        }
+
+        let mut world = World::new();
+        world.insert_resource(registry.clone());
+        let mut planet = LinearCurve::new();
+        planet.insert_keyframe(0, 0.0f32);
+        planet.insert_keyframe(10, 5.0);
+        let planet = world.spawn((planet, StateLifetime::new(0))).id();
+        let mut target = SteppedCurve::new();
+        target.insert_keyframe(5, planet);
+        let ship = world.spawn(target).id();
+
+        let ron = DynamicScene::from_world(&world)
+            .serialize(&registry.read())
+            .unwrap();
+        let mut deserializer = ron::de::Deserializer::from_str(&ron).unwrap();
+        let scene = SceneDeserializer {
+            type_registry: &registry.read(),
+        }
+        .deserialize(&mut deserializer)
+        .unwrap();
+
+        // Spawn some entities first so the loaded entities get different ids
+        let mut loaded = World::new();
+        loaded.insert_resource(registry.clone());
+        loaded.spawn_batch((0..10).map(|_| ()));
+        let mut entity_map = EntityHashMap::default();
+        scene.write_to_world(&mut loaded, &mut entity_map).unwrap();
+
+        let loaded_planet = entity_map[&planet];
+        let loaded_ship = entity_map[&ship];
+        assert_ne!(loaded_planet, planet);
+        let planet_curve = loaded.get::<LinearCurve<f32>>(loaded_planet).unwrap();
+        assert_eq!(planet_curve.get_state(5), Some(2.5));
+        assert_eq!(
+            loaded.get::<StateLifetime>(loaded_planet),
+            Some(&StateLifetime::new(0))
+        );
+        let target_curve = loaded.get::<SteppedCurve<Entity>>(loaded_ship).unwrap();
+        assert_eq!(target_curve.get_state(7), Some(loaded_planet));
+    }
+}
@@ -0,0 +1,549 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    ops::{Bound, Range, RangeBounds},
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(all(feature = "type_path", not(feature = "reflect")))]
+use bevy::reflect::TypePath;
+
+#[cfg(feature = "reflect")]
+use bevy::reflect::Reflect;
+
+/// The storage that a [`Curve`](crate::curves::Curve) keeps its keyframes in, ordered by tick.
+///
+/// Every curve type takes its storage as the last generic parameter, defaulting to a [`BTreeMap`]. Pick a different
+/// backend depending on how the curve is used:
+///
+/// - [`BTreeMap`] handles any pattern of inserts and removals and any number of keyframes.
+/// - [`VecStorage`] keeps keyframes in a sorted [`Vec`]. Lookups and appending to the end are cheap and there is a
+///   single allocation, but inserting or removing in the middle shifts every keyframe after it.
+/// - [`BoundedStorage`] keeps at most the `N` newest keyframes in a sorted [`VecDeque`] and drops the oldest keyframe
+///   when a newer one is inserted while full. Made for rollback where only the last few ticks are kept.
+///
+/// ```rust,ignore
+/// commands.spawn(LinearCurve::<Position, GameTick, BoundedStorage<GameTick, Position, 16>>::default());
+/// ```
+pub trait CurveStorage<K, V>: Default {
+    /// Inserts the keyframe on the given tick, replacing any keyframe already on it.
+    ///
+    /// Returns the keyframe that the storage had no room for: the oldest keyframe if it was dropped to make room for
+    /// this one, or this keyframe itself if it wasn't stored. Storages without a limit always return `None`.
+    fn insert(&mut self, tick: K, keyframe: V) -> Option<(K, V)>;
+
+    /// Removes and returns the keyframe on the given tick if there is one
+    fn remove(&mut self, tick: &K) -> Option<V>;
+
+    /// Returns the keyframe on the given tick if there is one
+    fn get(&self, tick: &K) -> Option<&V>;
+
+    /// Mutable version of [`CurveStorage::get`]
+    fn get_mut(&mut self, tick: &K) -> Option<&mut V>;
+
+    /// Returns every keyframe within the range of ticks, ordered by tick. `(Bound::Unbounded, Bound::Unbounded)` returns
+    /// every keyframe and a range without any ticks, such as one that starts after it ends, returns none
+    fn range<'a>(
+        &'a self,
+        range: (Bound<K>, Bound<K>),
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a V)>
+    where
+        K: 'a,
+        V: 'a;
+
+    /// Mutable version of [`CurveStorage::range`]
+    fn range_mut<'a>(
+        &'a mut self,
+        range: (Bound<K>, Bound<K>),
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a mut V)>
+    where
+        K: 'a,
+        V: 'a;
+
+    /// Keeps only the keyframes that `keep` returns true for
+    fn retain(&mut self, keep: impl FnMut(&K, &mut V) -> bool);
+
+    /// Returns how many keyframes are stored
+    fn len(&self) -> usize;
+
+    /// Returns true if no keyframes are stored
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Returns false if the range contains no ticks because it starts after it ends or excludes the same tick on both
+/// ends. [`BTreeMap::range`] panics on these ranges.
+pub(crate) fn is_valid_range<K: Ord>(range: &(Bound<K>, Bound<K>)) -> bool {
+    match range {
+        (
+            Bound::Included(start) | Bound::Excluded(start),
+            Bound::Included(end) | Bound::Excluded(end),
+        ) if start > end => false,
+        (Bound::Excluded(start), Bound::Excluded(end)) => start != end,
+        _ => true,
+    }
+}
+
+impl<K: Ord, V> CurveStorage<K, V> for BTreeMap<K, V> {
+    fn insert(&mut self, tick: K, keyframe: V) -> Option<(K, V)> {
+        BTreeMap::insert(self, tick, keyframe);
+        None
+    }
+
+    fn remove(&mut self, tick: &K) -> Option<V> {
+        BTreeMap::remove(self, tick)
+    }
+
+    fn get(&self, tick: &K) -> Option<&V> {
+        BTreeMap::get(self, tick)
+    }
+
+    fn get_mut(&mut self, tick: &K) -> Option<&mut V> {
+        BTreeMap::get_mut(self, tick)
+    }
+
+    fn range<'a>(
+        &'a self,
+        range: (Bound<K>, Bound<K>),
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a V)>
+    where
+        K: 'a,
+        V: 'a,
+    {
+        is_valid_range(&range)
+            .then(|| BTreeMap::range(self, range))
+            .into_iter()
+            .flatten()
+    }
+
+    fn range_mut<'a>(
+        &'a mut self,
+        range: (Bound<K>, Bound<K>),
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a mut V)>
+    where
+        K: 'a,
+        V: 'a,
+    {
+        is_valid_range(&range)
+            .then(|| BTreeMap::range_mut(self, range))
+            .into_iter()
+            .flatten()
+    }
+
+    fn retain(&mut self, keep: impl FnMut(&K, &mut V) -> bool) {
+        BTreeMap::retain(self, keep);
+    }
+
+    fn len(&self) -> usize {
+        BTreeMap::len(self)
+    }
+}
+
+/// Returns the indices of the keyframes within `range`, using `partition_point` to binary search the sorted ticks
+fn index_range<K: Ord>(
+    range: &impl RangeBounds<K>,
+    len: usize,
+    partition_point: impl Fn(&dyn Fn(&K) -> bool) -> usize,
+) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(start) => partition_point(&|tick| tick < start),
+        Bound::Excluded(start) => partition_point(&|tick| tick <= start),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(end) => partition_point(&|tick| tick <= end),
+        Bound::Excluded(end) => partition_point(&|tick| tick < end),
+        Bound::Unbounded => len,
+    };
+    start..end.max(start)
+}
+
+/// The serialized keyframes of a [`VecStorage`] or [`BoundedStorage`], checked before they are stored
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct SerializedKeyframes<K, V> {
+    keyframes: Vec<(K, V)>,
+}
+
+#[cfg(feature = "serde")]
+impl<K: Ord, V> SerializedKeyframes<K, V> {
+    /// Returns the keyframes if they are sorted by tick without any tick appearing twice
+    fn sorted(self) -> Result<Vec<(K, V)>, &'static str> {
+        if !self.keyframes.windows(2).all(|pair| pair[0].0 < pair[1].0) {
+            return Err("keyframes are not sorted by tick or have duplicate ticks");
+        }
+        Ok(self.keyframes)
+    }
+}
+
+/// A [`CurveStorage`] keeping keyframes in a [`Vec`] sorted by tick. See [`CurveStorage`]
+///
+/// Deserializing fails if the keyframes aren't sorted by tick.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "SerializedKeyframes<K, V>",
+        bound(deserialize = "K: Ord + Deserialize<'de>, V: Deserialize<'de>")
+    )
+)]
+#[cfg_attr(all(feature = "type_path", not(feature = "reflect")), derive(TypePath))]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+pub struct VecStorage<K, V> {
+    keyframes: Vec<(K, V)>,
+}
+
+impl<K, V> Default for VecStorage<K, V> {
+    fn default() -> Self {
+        VecStorage { keyframes: vec![] }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<K: Ord, V> TryFrom<SerializedKeyframes<K, V>> for VecStorage<K, V> {
+    type Error = &'static str;
+
+    fn try_from(serialized: SerializedKeyframes<K, V>) -> Result<Self, Self::Error> {
+        Ok(VecStorage {
+            keyframes: serialized.sorted()?,
+        })
+    }
+}
+
+impl<K: Ord, V> VecStorage<K, V> {
+    /// Returns the index of the keyframe on the tick, or the index it would be inserted at
+    fn search(&self, tick: &K) -> Result<usize, usize> {
+        self.keyframes
+            .binary_search_by(|(frame_tick, _)| frame_tick.cmp(tick))
+    }
+
+    fn index_range(&self, range: &impl RangeBounds<K>) -> Range<usize> {
+        index_range(range, self.keyframes.len(), |predicate| {
+            self.keyframes.partition_point(|(tick, _)| predicate(tick))
+        })
+    }
+}
+
+impl<K: Ord, V> CurveStorage<K, V> for VecStorage<K, V> {
+    fn insert(&mut self, tick: K, keyframe: V) -> Option<(K, V)> {
+        // Keyframes are usually appended so check the end before searching
+        if self
+            .keyframes
+            .last()
+            .is_none_or(|(last_tick, _)| *last_tick < tick)
+        {
+            self.keyframes.push((tick, keyframe));
+            return None;
+        }
+        match self.search(&tick) {
+            Ok(index) => self.keyframes[index].1 = keyframe,
+            Err(index) => self.keyframes.insert(index, (tick, keyframe)),
+        }
+        None
+    }
+
+    fn remove(&mut self, tick: &K) -> Option<V> {
+        let index = self.search(tick).ok()?;
+        Some(self.keyframes.remove(index).1)
+    }
+
+    fn get(&self, tick: &K) -> Option<&V> {
+        let index = self.search(tick).ok()?;
+        Some(&self.keyframes[index].1)
+    }
+
+    fn get_mut(&mut self, tick: &K) -> Option<&mut V> {
+        let index = self.search(tick).ok()?;
+        Some(&mut self.keyframes[index].1)
+    }
+
+    fn range<'a>(
+        &'a self,
+        range: (Bound<K>, Bound<K>),
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a V)>
+    where
+        K: 'a,
+        V: 'a,
+    {
+        let indices = self.index_range(&range);
+        self.keyframes[indices]
+            .iter()
+            .map(|(tick, keyframe)| (tick, keyframe))
+    }
+
+    fn range_mut<'a>(
+        &'a mut self,
+        range: (Bound<K>, Bound<K>),
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a mut V)>
+    where
+        K: 'a,
+        V: 'a,
+    {
+        let indices = self.index_range(&range);
+        self.keyframes[indices]
+            .iter_mut()
+            .map(|(tick, keyframe)| (&*tick, keyframe))
+    }
+
+    fn retain(&mut self, mut keep: impl FnMut(&K, &mut V) -> bool) {
+        self.keyframes
+            .retain_mut(|(tick, keyframe)| keep(tick, keyframe));
+    }
+
+    fn len(&self) -> usize {
+        self.keyframes.len()
+    }
+}
+
+/// A [`CurveStorage`] keeping at most the `N` newest keyframes in a [`VecDeque`] sorted by tick. See [`CurveStorage`]
+///
+/// ## Explanation:
+///
+/// - Once `N` keyframes are stored, inserting a keyframe on a new tick drops the oldest keyframe and returns it from
+///   [`CurveStorage::insert`].
+/// - Inserting a keyframe older than every stored keyframe while full doesn't store it, since it would be dropped
+///   straight away, and returns it from [`CurveStorage::insert`].
+/// - Keyframes are kept sorted rather than indexed by tick, so any tick type works and the ticks don't need to be
+///   consecutive. Lookups binary search the `N` keyframes.
+/// - The storage allocates once, on the first insert, and never grows past `N`.
+/// - Deserializing fails if the keyframes aren't sorted by tick or there are more than `N` of them.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "SerializedKeyframes<K, V>",
+        bound(deserialize = "K: Ord + Deserialize<'de>, V: Deserialize<'de>")
+    )
+)]
+#[cfg_attr(all(feature = "type_path", not(feature = "reflect")), derive(TypePath))]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+pub struct BoundedStorage<K, V, const N: usize> {
+    keyframes: VecDeque<(K, V)>,
+}
+
+impl<K, V, const N: usize> Default for BoundedStorage<K, V, N> {
+    fn default() -> Self {
+        BoundedStorage {
+            keyframes: VecDeque::new(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<K: Ord, V, const N: usize> TryFrom<SerializedKeyframes<K, V>> for BoundedStorage<K, V, N> {
+    type Error = &'static str;
+
+    fn try_from(serialized: SerializedKeyframes<K, V>) -> Result<Self, Self::Error> {
+        let keyframes = serialized.sorted()?;
+        if keyframes.len() > N {
+            return Err("more keyframes than the storage can hold");
+        }
+        Ok(BoundedStorage {
+            keyframes: keyframes.into(),
+        })
+    }
+}
+
+impl<K: Ord, V, const N: usize> BoundedStorage<K, V, N> {
+    /// Returns the index of the keyframe on the tick, or the index it would be inserted at
+    fn search(&self, tick: &K) -> Result<usize, usize> {
+        self.keyframes
+            .binary_search_by(|(frame_tick, _)| frame_tick.cmp(tick))
+    }
+
+    fn index_range(&self, range: &impl RangeBounds<K>) -> Range<usize> {
+        index_range(range, self.keyframes.len(), |predicate| {
+            self.keyframes.partition_point(|(tick, _)| predicate(tick))
+        })
+    }
+}
+
+impl<K: Ord, V, const N: usize> CurveStorage<K, V> for BoundedStorage<K, V, N> {
+    fn insert(&mut self, tick: K, keyframe: V) -> Option<(K, V)> {
+        if N == 0 {
+            return Some((tick, keyframe));
+        }
+        if self.keyframes.capacity() == 0 {
+            self.keyframes.reserve_exact(N);
+        }
+        let index = match self.search(&tick) {
+            Ok(index) => {
+                self.keyframes[index].1 = keyframe;
+                return None;
+            }
+            Err(index) => index,
+        };
+        if self.keyframes.len() < N {
+            self.keyframes.insert(index, (tick, keyframe));
+            return None;
+        }
+        if index == 0 {
+            return Some((tick, keyframe));
+        }
+        let evicted = self.keyframes.pop_front();
+        self.keyframes.insert(index - 1, (tick, keyframe));
+        evicted
+    }
+
+    fn remove(&mut self, tick: &K) -> Option<V> {
+        let index = self.search(tick).ok()?;
+        self.keyframes.remove(index).map(|(_, keyframe)| keyframe)
+    }
+
+    fn get(&self, tick: &K) -> Option<&V> {
+        let index = self.search(tick).ok()?;
+        Some(&self.keyframes[index].1)
+    }
+
+    fn get_mut(&mut self, tick: &K) -> Option<&mut V> {
+        let index = self.search(tick).ok()?;
+        Some(&mut self.keyframes[index].1)
+    }
+
+    fn range<'a>(
+        &'a self,
+        range: (Bound<K>, Bound<K>),
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a V)>
+    where
+        K: 'a,
+        V: 'a,
+    {
+        let indices = self.index_range(&range);
+        self.keyframes
+            .range(indices)
+            .map(|(tick, keyframe)| (tick, keyframe))
+    }
+
+    fn range_mut<'a>(
+        &'a mut self,
+        range: (Bound<K>, Bound<K>),
+    ) -> impl DoubleEndedIterator<Item = (&'a K, &'a mut V)>
+    where
+        K: 'a,
+        V: 'a,
+    {
+        let indices = self.index_range(&range);
+        self.keyframes
+            .range_mut(indices)
+            .map(|(tick, keyframe)| (&*tick, keyframe))
+    }
+
+    fn retain(&mut self, mut keep: impl FnMut(&K, &mut V) -> bool) {
+        self.keyframes
+            .retain_mut(|(tick, keyframe)| keep(tick, keyframe));
+    }
+
+    fn len(&self) -> usize {
+        self.keyframes.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        ops::Bound::{Excluded, Included, Unbounded},
+    };
+
+    use super::{BoundedStorage, CurveStorage, VecStorage};
+
+    /// Runs the same inserts, removals and range queries against the storage and a [`BTreeMap`]
+    fn assert_matches_btree<S: CurveStorage<u64, u32>>() {
+        let mut storage = S::default();
+        let mut expected = BTreeMap::new();
+        for tick in [5u64, 1, 9, 3, 7, 5, 11] {
+            CurveStorage::insert(&mut storage, tick, tick as u32 * 10);
+            expected.insert(tick, tick as u32 * 10);
+        }
+        assert_eq!(storage.remove(&3), expected.remove(&3));
+        assert_eq!(storage.remove(&4), expected.remove(&4));
+        if let Some(keyframe) = CurveStorage::get_mut(&mut storage, &7) {
+            *keyframe += 1;
+        }
+        *expected.get_mut(&7).unwrap() += 1;
+
+        assert_eq!(CurveStorage::len(&storage), expected.len());
+        assert_eq!(CurveStorage::get(&storage, &9), expected.get(&9));
+        for (start, end) in [(0, 20), (2, 8), (5, 5), (6, 6), (9, 12)] {
+            assert_eq!(
+                CurveStorage::range(&storage, (Included(start), Included(end))).collect::<Vec<_>>(),
+                expected.range(start..=end).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                CurveStorage::range(&storage, (Included(start), Excluded(end)))
+                    .rev()
+                    .collect::<Vec<_>>(),
+                expected.range(start..end).rev().collect::<Vec<_>>()
+            );
+        }
+        // Ranges without any ticks are empty rather than panicking like `BTreeMap::range`
+        for range in [(Included(9), Included(2)), (Excluded(5), Excluded(5))] {
+            assert_eq!(CurveStorage::range(&storage, range).count(), 0);
+            assert_eq!(CurveStorage::range_mut(&mut storage, range).count(), 0);
+        }
+        CurveStorage::retain(&mut storage, |tick, _| *tick > 5);
+        expected.retain(|tick, _| *tick > 5);
+        assert_eq!(
+            CurveStorage::range(&storage, (Unbounded, Unbounded)).collect::<Vec<_>>(),
+            expected.range(..).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_curve_storages() {
+        assert_matches_btree::<BTreeMap<u64, u32>>();
+        assert_matches_btree::<VecStorage<u64, u32>>();
+        assert_matches_btree::<BoundedStorage<u64, u32, 16>>();
+
+        let mut bounded = BoundedStorage::<u64, u32, 3>::default();
+        let evicted = (0..5)
+            .filter_map(|tick| bounded.insert(tick, tick as u32))
+            .collect::<Vec<_>>();
+        assert_eq!(evicted, vec![(0, 0), (1, 1)]);
+        // Older than everything stored so it is handed straight back
+        assert_eq!(bounded.insert(0, 0), Some((0, 0)));
+        assert_eq!(bounded.insert(3, 30), None);
+        assert_eq!(
+            bounded.range((Unbounded, Unbounded)).collect::<Vec<_>>(),
+            vec![(&2, &2), (&3, &30), (&4, &4)]
+        );
+        assert_eq!(bounded.insert(10, 10), Some((2, 2)));
+        assert_eq!(bounded.insert(5, 5), Some((3, 30)));
+        assert_eq!(
+            bounded
+                .range((Unbounded, Unbounded))
+                .map(|(tick, _)| *tick)
+                .collect::<Vec<_>>(),
+            vec![4, 5, 10]
+        );
+    }
+
+    #[cfg(feature = "asset")]
+    #[test]
+    fn test_deserialize_storages() {
+        use bevy::asset::ron;
+
+        let bounded: BoundedStorage<u64, u32, 3> =
+            ron::de::from_str("(keyframes: [(1, 10), (4, 40)])").unwrap();
+        assert_eq!(bounded.len(), 2);
+        let vec: VecStorage<u64, u32> =
+            ron::de::from_str(&ron::ser::to_string(&bounded).unwrap()).unwrap();
+        assert_eq!(vec.get(&4), Some(&40));
+
+        for unsorted in [
+            "(keyframes: [(4, 40), (1, 10)])",
+            "(keyframes: [(1, 10), (1, 11)])",
+        ] {
+            assert!(ron::de::from_str::<VecStorage<u64, u32>>(unsorted).is_err());
+            assert!(ron::de::from_str::<BoundedStorage<u64, u32, 3>>(unsorted).is_err());
+        }
+        assert!(ron::de::from_str::<BoundedStorage<u64, u32, 1>>(
+            "(keyframes: [(1, 10), (4, 40)])"
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,195 @@
+use std::{cmp::Ordering, fmt::Debug};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(all(feature = "type_path", not(feature = "reflect")))]
+use bevy::reflect::TypePath;
+
+#[cfg(feature = "reflect")]
+use bevy::reflect::Reflect;
+
+/// A trait that must be implemented to allow a type to be used as the tick that keyframes are stored under.
+///
+/// Keyframes are stored and ordered using the types [`Ord`] implementation. Implemented for the unsigned integers,
+/// `i32`, `i64`, [`WrappingTick`] and [`SecondsTick`]. [`GameTick`](crate::GameTick) is the default tick for every curve.
+///
+/// Ticks that wrap around implement [`Ord`] as a wrapping comparison, see [`WrappingTick`].
+pub trait Tick: Copy + Ord + Debug + Send + Sync + 'static {
+    /// Returns how many ticks `other` comes after `self`. Negative if `other` comes before `self`.
+    fn distance(&self, other: &Self) -> f64;
+
+    /// Splits a fractional tick into the tick it falls within and how far into that tick it is.
+    ///
+    /// Returns `None` if the fractional tick can't be represented by this tick type.
+    fn split_fractional(tick: f64) -> Option<(Self, f64)>;
+
+    /// Returns the tick as a continuous value, the inverse of [`Tick::split_fractional`].
+    ///
+    /// Used to map ticks onto the `f32` time that bevy's [`Curve`](bevy::math::curve::Curve) trait is sampled with.
+    fn as_f64(&self) -> f64;
+}
+
+macro_rules! impl_integer_tick {
+    ($($ty:ty),*) => {
+        $(
+            impl Tick for $ty {
+                fn distance(&self, other: &Self) -> f64 {
+                    if other >= self {
+                        (other - self) as f64
+                    } else {
+                        -((self - other) as f64)
+                    }
+                }
+
+                fn split_fractional(tick: f64) -> Option<(Self, f64)> {
+                    let whole = tick.floor();
+                    if !whole.is_finite() || whole < <$ty>::MIN as f64 || whole > <$ty>::MAX as f64 {
+                        return None;
+                    }
+                    Some((whole as $ty, tick - whole))
+                }
+
+                fn as_f64(&self) -> f64 {
+                    *self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_tick!(u8, u16, u32, u64, usize, i32, i64);
+
+/// A `u32` tick that wraps around back to 0, such as a tick sent over the network.
+///
+/// ## Explanation:
+///
+/// - Ordered as a sequence number: a tick is before every tick less than half the `u32` range after it, so
+///   `WrappingTick(u32::MAX) < WrappingTick(0)`. Curves store their keyframes in this order so they are kept in order
+///   across the wrap point.
+/// - The ordering is only consistent for ticks less than half the range, 2^31 ticks, apart. Every keyframe of a curve
+///   must be within that window, so remove old keyframes with [`CurveTrait::remove_past_keyframes`](crate::curves::CurveTrait::remove_past_keyframes)
+///   long before the curve wraps all the way around.
+/// - [`Tick::distance`] counts the ticks across the wrap point.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(all(feature = "type_path", not(feature = "reflect")), derive(TypePath))]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+pub struct WrappingTick(pub u32);
+
+impl PartialOrd for WrappingTick {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for WrappingTick {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.0.wrapping_sub(other.0) as i32 {
+            // Exactly half the range apart, fall back to the plain ordering so the comparison stays antisymmetric
+            i32::MIN => self.0.cmp(&other.0),
+            difference => difference.cmp(&0),
+        }
+    }
+}
+
+impl Tick for WrappingTick {
+    fn distance(&self, other: &Self) -> f64 {
+        other.0.wrapping_sub(self.0) as i32 as f64
+    }
+
+    fn split_fractional(tick: f64) -> Option<(Self, f64)> {
+        u32::split_fractional(tick).map(|(tick, fraction)| (WrappingTick(tick), fraction))
+    }
+
+    fn as_f64(&self) -> f64 {
+        self.0 as f64
+    }
+}
+
+/// A tick measured in continuous seconds, such as the time in an editor.
+///
+/// Ordered using [`f64::total_cmp`]. Since the tick is already continuous, fractional sampling uses the exact time.
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(all(feature = "type_path", not(feature = "reflect")), derive(TypePath))]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+pub struct SecondsTick(pub f64);
+
+impl PartialEq for SecondsTick {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SecondsTick {}
+
+impl PartialOrd for SecondsTick {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SecondsTick {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl Tick for SecondsTick {
+    fn distance(&self, other: &Self) -> f64 {
+        other.0 - self.0
+    }
+
+    fn split_fractional(tick: f64) -> Option<(Self, f64)> {
+        tick.is_finite().then_some((SecondsTick(tick), 0.0))
+    }
+
+    fn as_f64(&self) -> f64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SecondsTick, Tick, WrappingTick};
+    use crate::prelude::{CurveTrait, LinearCurve};
+
+    #[test]
+    fn test_tick_conversions() {
+        assert_eq!(10u32.distance(&6), -4.0);
+        assert_eq!(SecondsTick(1.5).distance(&SecondsTick(2.0)), 0.5);
+        assert_eq!(u64::split_fractional(-0.5), None);
+        assert_eq!(i64::split_fractional(-0.5), Some((-1, 0.5)));
+    }
+
+    #[test]
+    fn test_wrapping_tick() {
+        let before_wrap = WrappingTick(u32::MAX - 1);
+        let after_wrap = WrappingTick(2);
+        assert!(before_wrap < after_wrap);
+        assert_eq!(before_wrap.distance(&after_wrap), 4.0);
+        assert_eq!(after_wrap.distance(&before_wrap), -4.0);
+        assert!(WrappingTick(0) < WrappingTick(1 << 31));
+        assert!(WrappingTick(1 << 31) > WrappingTick(0));
+
+        // Keyframes stay in order across the wrap point
+        let mut curve = LinearCurve::<f32, WrappingTick>::new();
+        curve.insert_keyframe(WrappingTick(6), 16.0);
+        curve.insert_keyframe(WrappingTick(u32::MAX - 9), 0.0);
+        curve.insert_keyframe(WrappingTick(u32::MAX), 9.0);
+        assert_eq!(
+            curve.range(..).map(|(tick, _)| tick.0).collect::<Vec<_>>(),
+            vec![u32::MAX - 9, u32::MAX, 6]
+        );
+        assert_eq!(curve.get_state(WrappingTick(u32::MAX - 4)), Some(5.0));
+        assert_eq!(curve.get_state(WrappingTick(2)), Some(12.0));
+        assert_eq!(curve.get_state(WrappingTick(u32::MAX - 10)), None);
+
+        curve.remove_future_keyframes(&WrappingTick(0));
+        assert_eq!(
+            curve.last_keyframe().map(|(tick, _)| tick.0),
+            Some(u32::MAX)
+        );
+    }
+}
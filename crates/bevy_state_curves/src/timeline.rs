@@ -0,0 +1,259 @@
+use bevy::{
+    app::{App, FixedUpdate, Plugin, Update},
+    prelude::{Event, EventWriter, IntoSystemConfigs, IntoSystemSetConfigs, Res, ResMut, Resource},
+};
+
+use crate::{
+    plugin::{CurveSystems, ViewedTick},
+    GameTick,
+};
+
+/// Plugin that adds the [`Timeline`] resource and advances it every [`FixedUpdate`].
+///
+/// The viewed tick of the timeline is mirrored into the [`ViewedTick`] so that every curve registered with the
+/// [`CurvesPlugin`](crate::plugin::CurvesPlugin) follows the timeline. A [`ViewedTickChanged`] event is sent every time the viewed tick moves.
+///
+/// When using this plugin drive the viewed tick through the [`Timeline`] rather than writing the [`ViewedTick`] directly.
+pub struct TimelinePlugin;
+
+impl Plugin for TimelinePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Timeline>();
+        app.init_resource::<ViewedTick>();
+        app.add_event::<ViewedTickChanged>();
+        app.configure_sets(
+            Update,
+            CurveSystems::SyncTimeline.before(CurveSystems::SampleState),
+        );
+        app.add_systems(
+            FixedUpdate,
+            advance_timeline.in_set(CurveSystems::AdvanceTimeline),
+        );
+        app.add_systems(Update, sync_viewed_tick.in_set(CurveSystems::SyncTimeline));
+    }
+}
+
+/// The direction that the viewed tick of a [`Timeline`] moves in during playback
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaybackDirection {
+    /// The viewed tick moves towards the simulation head
+    #[default]
+    Forward,
+    /// The viewed tick moves back towards tick 0
+    Backward,
+}
+
+/// Sent whenever the [`Timeline`] moves the [`ViewedTick`]
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ViewedTickChanged {
+    /// The previously viewed tick
+    pub previous: GameTick,
+    /// The newly viewed tick
+    pub current: GameTick,
+}
+
+/// The timeline of the game. Tracks the simulation head, which is the latest tick that has been simulated, and the
+/// viewed tick, which is the tick that is currently being displayed.
+///
+/// ## Explanation:
+///
+/// - If `simulating` is true then the simulation head advances one tick every [`FixedUpdate`].
+/// - If `paused` is false then the viewed tick moves `rate` ticks every [`FixedUpdate`] in the playback `direction`.
+/// - The viewed tick is always clamped between 0 and the simulation head plus the `horizon`.
+#[derive(Resource, Debug, Clone)]
+pub struct Timeline {
+    simulation_tick: GameTick,
+    viewed_tick: GameTick,
+    /// How many ticks the viewed tick moves every [`FixedUpdate`] while playing. Fractional rates are accumulated
+    /// between steps so a rate of 0.5 moves the viewed tick once every two steps.
+    pub rate: f64,
+    /// The direction the viewed tick moves in while playing
+    pub direction: PlaybackDirection,
+    /// If the viewed tick is paused
+    pub paused: bool,
+    /// If the simulation head is advancing
+    pub simulating: bool,
+    /// How many ticks past the simulation head have valid state and can be viewed
+    pub horizon: GameTick,
+    /// Fractional ticks of playback that haven't been applied to the viewed tick yet
+    progress: f64,
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Timeline {
+            simulation_tick: 0,
+            viewed_tick: 0,
+            rate: 1.0,
+            direction: PlaybackDirection::Forward,
+            paused: false,
+            simulating: true,
+            horizon: 0,
+            progress: 0.0,
+        }
+    }
+}
+
+impl Timeline {
+    /// Creates a new [`Timeline`] that allows viewing up to `horizon` ticks past the simulation head
+    pub fn with_horizon(horizon: GameTick) -> Timeline {
+        Timeline {
+            horizon,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the simulation head, the latest tick that has been simulated
+    pub fn simulation_tick(&self) -> GameTick {
+        self.simulation_tick
+    }
+
+    /// Returns the tick that is currently being viewed
+    pub fn viewed_tick(&self) -> GameTick {
+        self.viewed_tick
+    }
+
+    /// Returns the viewed tick including the playback that has built up since the last fixed step.
+    ///
+    /// Pass in `Time<Fixed>::overstep_fraction` and sample curves with [`CurveTrait::get_state_at`](crate::curves::CurveTrait::get_state_at)
+    /// to smoothly render state in between fixed steps.
+    pub fn viewed_tick_fractional(&self, overstep_fraction: f64) -> f64 {
+        if self.paused {
+            return self.viewed_tick as f64;
+        }
+        let offset = self.progress + self.rate.max(0.0) * overstep_fraction;
+        let tick = match self.direction {
+            PlaybackDirection::Forward => self.viewed_tick as f64 + offset,
+            PlaybackDirection::Backward => self.viewed_tick as f64 - offset,
+        };
+        tick.clamp(0.0, self.max_viewable_tick() as f64)
+    }
+
+    /// Returns the latest tick that can be viewed. This is the simulation head plus the `horizon`
+    pub fn max_viewable_tick(&self) -> GameTick {
+        self.simulation_tick.saturating_add(self.horizon)
+    }
+
+    /// Sets the simulation head, clamping the viewed tick if it is now past the horizon
+    pub fn set_simulation_tick(&mut self, tick: GameTick) {
+        self.simulation_tick = tick;
+        self.viewed_tick = self.viewed_tick.min(self.max_viewable_tick());
+    }
+
+    /// Starts moving the viewed tick
+    pub fn play(&mut self) {
+        self.paused = false;
+    }
+
+    /// Stops moving the viewed tick
+    pub fn pause(&mut self) {
+        self.paused = true;
+        self.progress = 0.0;
+    }
+
+    /// Moves the viewed tick to the given tick, clamped to the viewable range
+    pub fn scrub_to(&mut self, tick: GameTick) {
+        self.viewed_tick = tick.min(self.max_viewable_tick());
+        self.progress = 0.0;
+    }
+
+    /// Moves the viewed tick by the given amount of ticks, clamped to the viewable range
+    pub fn scrub_by(&mut self, ticks: i64) {
+        let tick = self.viewed_tick.saturating_add_signed(ticks);
+        self.scrub_to(tick);
+    }
+
+    /// Moves the viewed tick to the simulation head
+    pub fn view_present(&mut self) {
+        self.scrub_to(self.simulation_tick);
+    }
+
+    /// Returns true if the viewed tick is the simulation head
+    pub fn is_viewing_present(&self) -> bool {
+        self.viewed_tick == self.simulation_tick
+    }
+
+    /// Advances the timeline by a single fixed step.
+    ///
+    /// This is called automatically by the [`TimelinePlugin`] every [`FixedUpdate`].
+    pub fn step(&mut self) {
+        if self.simulating {
+            self.simulation_tick = self.simulation_tick.saturating_add(1);
+        }
+        if self.paused {
+            return;
+        }
+
+        self.progress += self.rate.max(0.0);
+        let ticks = self.progress.floor();
+        self.progress -= ticks;
+        let ticks = ticks as GameTick;
+
+        let tick = match self.direction {
+            PlaybackDirection::Forward => self.viewed_tick.saturating_add(ticks),
+            PlaybackDirection::Backward => self.viewed_tick.saturating_sub(ticks),
+        };
+        self.viewed_tick = tick.min(self.max_viewable_tick());
+    }
+}
+
+/// Advances the [`Timeline`] every fixed step
+fn advance_timeline(mut timeline: ResMut<Timeline>) {
+    timeline.step();
+}
+
+/// Mirrors the [`Timeline`] viewed tick into the [`ViewedTick`] and sends a [`ViewedTickChanged`] event if it moved
+fn sync_viewed_tick(
+    timeline: Res<Timeline>,
+    mut viewed_tick: ResMut<ViewedTick>,
+    mut events: EventWriter<ViewedTickChanged>,
+) {
+    if viewed_tick.0 == timeline.viewed_tick() {
+        return;
+    }
+    events.send(ViewedTickChanged {
+        previous: viewed_tick.0,
+        current: timeline.viewed_tick(),
+    });
+    viewed_tick.0 = timeline.viewed_tick();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PlaybackDirection, Timeline};
+
+    #[test]
+    fn test_timeline_playback() {
+        let mut timeline = Timeline::with_horizon(2);
+
+        for _ in 0..5 {
+            timeline.step();
+        }
+        assert_eq!(timeline.simulation_tick(), 5);
+        assert_eq!(timeline.viewed_tick(), 5);
+
+        timeline.simulating = false;
+        for _ in 0..5 {
+            timeline.step();
+        }
+        // Clamped to the simulation head plus the horizon
+        assert_eq!(timeline.viewed_tick(), 7);
+
+        timeline.direction = PlaybackDirection::Backward;
+        timeline.rate = 0.5;
+        for _ in 0..4 {
+            timeline.step();
+        }
+        assert_eq!(timeline.viewed_tick(), 5);
+        assert_eq!(timeline.viewed_tick_fractional(0.5), 4.75);
+
+        timeline.pause();
+        timeline.step();
+        assert_eq!(timeline.viewed_tick(), 5);
+
+        timeline.scrub_to(100);
+        assert_eq!(timeline.viewed_tick(), 7);
+        timeline.scrub_by(-10);
+        assert_eq!(timeline.viewed_tick(), 0);
+    }
+}
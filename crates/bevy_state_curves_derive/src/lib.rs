@@ -0,0 +1,147 @@
+//! Derive macros for the keyframe traits of [`bevy_state_curves`](https://docs.rs/bevy_state_curves).
+//!
+//! Use these through the `derive` feature of `bevy_state_curves` rather than depending on this crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Field, Fields, Index};
+
+/// Derives `LinearKeyframe` by linearly interpolating every field of the struct.
+///
+/// Every field must implement `LinearKeyframe` for itself, which `bevy_state_curves` does for the numeric, glam,
+/// `Transform` and `Color` types. Fields can opt out of interpolation with an attribute:
+///
+/// - `#[keyframe(step)]` holds the value of the previous keyframe until the next keyframe is reached.
+/// - `#[keyframe(default)]` always uses the fields [`Default`] value, dropping the value of both keyframes. Use it
+///   for fields that are only meaningful on the keyframes themselves.
+///
+/// ```rust ignore
+/// #[derive(Clone, LinearKeyframe)]
+/// pub struct BodyRotationPoint {
+///     point: Vec2,
+///     #[keyframe(step)]
+///     orbits: u32,
+/// }
+/// ```
+#[proc_macro_derive(LinearKeyframe, attributes(keyframe))]
+pub fn derive_linear_keyframe(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_linear_keyframe(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `SteppedKeyframe`. The type only needs to implement [`Clone`].
+#[proc_macro_derive(SteppedKeyframe)]
+pub fn derive_stepped_keyframe(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_marker_keyframe(&input, quote!(SteppedKeyframe)).into()
+}
+
+/// Derives `PulseKeyframe`. The type only needs to implement [`Clone`].
+#[proc_macro_derive(PulseKeyframe)]
+pub fn derive_pulse_keyframe(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_marker_keyframe(&input, quote!(PulseKeyframe)).into()
+}
+
+/// How a single field is produced when interpolating between two keyframes
+enum FieldMode {
+    Lerp,
+    Step,
+    Default,
+}
+
+fn field_mode(field: &Field) -> syn::Result<FieldMode> {
+    let mut mode = FieldMode::Lerp;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("keyframe"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("step") {
+                mode = FieldMode::Step;
+                Ok(())
+            } else if meta.path.is_ident("default") {
+                mode = FieldMode::Default;
+                Ok(())
+            } else {
+                Err(meta.error("expected `step` or `default`"))
+            }
+        })?;
+    }
+    Ok(mode)
+}
+
+fn expand_linear_keyframe(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.span(),
+            "LinearKeyframe can only be derived for structs",
+        ));
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let next = format_ident!("next_frame_state");
+
+    let mut values = Vec::with_capacity(data.fields.len());
+    for (index, field) in data.fields.iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let index = Index::from(index);
+                quote!(#index)
+            }
+        };
+        let ty = &field.ty;
+        let value = match field_mode(field)? {
+            FieldMode::Lerp => quote! {
+                <#ty as ::bevy_state_curves::prelude::LinearKeyframe<#ty>>::lerp(
+                    &self.#member,
+                    &#next.#member,
+                    ratio,
+                )
+            },
+            FieldMode::Step => quote!(::core::clone::Clone::clone(&self.#member)),
+            FieldMode::Default => quote!(::core::default::Default::default()),
+        };
+        values.push((member, value));
+    }
+
+    let body = match &data.fields {
+        Fields::Named(_) => {
+            let fields = values.iter().map(|(member, value)| quote!(#member: #value));
+            quote!(#name { #(#fields),* })
+        }
+        Fields::Unnamed(_) => {
+            let fields = values.iter().map(|(_, value)| value);
+            quote!(#name ( #(#fields),* ))
+        }
+        Fields::Unit => quote!(#name),
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::bevy_state_curves::prelude::LinearKeyframe<#name #ty_generics>
+            for #name #ty_generics #where_clause
+        {
+            #[allow(unused_variables)]
+            fn lerp(&self, #next: &#name #ty_generics, ratio: f64) -> #name #ty_generics {
+                #body
+            }
+        }
+    })
+}
+
+fn expand_marker_keyframe(input: &DeriveInput, keyframe_trait: TokenStream2) -> TokenStream2 {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    quote! {
+        impl #impl_generics ::bevy_state_curves::prelude::#keyframe_trait<#name #ty_generics>
+            for #name #ty_generics #where_clause
+        {
+        }
+    }
+}